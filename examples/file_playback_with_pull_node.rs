@@ -4,9 +4,8 @@ extern crate libaudioverse;
 extern crate libaudioverse_sys;
 use libaudioverse::{
     nodes::{Node, PullNode},
-    Server,
+    Libaudioverse, Server,
 };
-use std::ffi::CString;
 use std::{env, iter};
 use std::{thread, time};
 
@@ -14,13 +13,13 @@ use std::{thread, time};
 fn main() {
     // Make a WavReader that reads the file provided as program argument.
     let fname = env::args().nth(1).expect("no file given");
-    libaudioverse::initialize().unwrap();
+    let lib = Libaudioverse::new().unwrap();
     let mut reader = hound::WavReader::open(fname.clone()).unwrap();
     let spec = reader.spec();
     println!("{}: {:?}", fname, spec);
-    let server = Server::construct(spec.sample_rate, 1024).unwrap();
+    let server = Server::construct(&lib, spec.sample_rate, 1024).unwrap();
     server
-        .set_output_device_details(&CString::new("default").unwrap(), spec.channels as i32, 2)
+        .set_default_output_device(spec.channels as i32, 2)
         .expect("Could not create default audio device");
 
     let samples_left = reader.len() as usize;
@@ -32,9 +31,9 @@ fn main() {
         duration
     );
 
-    let mut pull_node = PullNode::new(&server, spec.sample_rate, spec.channels as u32)
+    let pull_node = PullNode::new(&server, spec.sample_rate, spec.channels as u32)
         .expect("Could not create pull node");
-    let send_more_audio = |_n: &PullNode, _frames: i32, _channels: i32, buf: &mut [f32]| {
+    let send_more_audio = move |_n: &PullNode, _frames: i32, _channels: i32, buf: &mut [f32]| {
         // Chain the iterator over the audio data with an infinite iterator that always returns zero.
         // Then, zip it with an iterator over the buffer that needs to be filled.
         // This has the effect of writing audio to the buffer when available, or zero otherwise.
@@ -63,5 +62,4 @@ fn main() {
         .expect("Could not connect the pull node to the server for playback");
 
     thread::sleep(time::Duration::from_secs((duration + 1) as u64));
-    libaudioverse::shutdown().unwrap();
 }
@@ -2,29 +2,123 @@
 use super::*;
 use super::{libaudioverse_sys, server};
 use check;
-use std::ffi::CString;
-use std::os::raw;
+use decoders;
+use lav_ptr::LavPtr;
+use std::fs;
+use std::marker::PhantomData;
+use std::os::raw::c_int;
+use std::path::Path;
+use std::time::Duration;
 
 /// Buffers store un-encoded float32 audio data at the sampling rate of the server. They can be loaded from files or arrays, and will resample the data exactly once when loaded. Buffers are most commonly used with buffer nodes.
-/// Save for the contained audio data, buffers are stateless; using them requires coupling them with a node. Since buffers are quite large, using a cache is recommended. Buffers may safely be used in more than one place at a time. Modifying a buffer’s audio data while it is in use will result in an error.
-pub struct Buffer {
+/// Save for the contained audio data, buffers are stateless; using them requires coupling them with a node. Since buffers are quite large, using a cache such as `BufferCache` is recommended. Buffers may safely be used in more than one place at a time. Modifying a buffer’s audio data while it is in use will result in an error.
+/// `B` is the brand of the server, which must match the brand of the nodes the buffer is assigned to.
+pub struct Buffer<'server, B = server::Unbranded> {
     // make handle visible for  BufferProperty's usage
-    pub(crate) handle: libaudioverse_sys::LavHandle,
+    pub(crate) handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // buffers cannot outlive the library instance of the server they were created on
 }
 
-impl Buffer {
+impl<'server, B> Clone for Buffer<'server, B> {
+    fn clone(&self) -> Buffer<'server, B> {
+        Buffer {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Buffer<'server, B> {
     /// Creates a new audio buffer.
-    pub fn new(server: &server::Server) -> Result<Buffer> {
+    pub fn new<M>(server: &server::Server<'server, M, B>) -> Result<Buffer<'server, B>> {
         let mut buf_handle: libaudioverse_sys::LavHandle = 0;
-        check(unsafe { libaudioverse_sys::Lav_createBuffer(server.handle, &mut buf_handle) })?;
-        Ok(Buffer { handle: buf_handle })
+        check(unsafe { libaudioverse_sys::Lav_createBuffer(*server.handle, &mut buf_handle) })?;
+        Ok(Buffer {
+            handle: LavPtr::new(buf_handle)?,
+            server: PhantomData,
+        })
+    }
+
+    /// Creates a buffer holding the interleaved samples in `data`, resampling them to the sampling rate of the server.
+    pub fn from_samples<M>(
+        server: &server::Server<'server, M, B>,
+        sampling_rate: u32,
+        channels: u32,
+        data: &[f32],
+    ) -> Result<Buffer<'server, B>> {
+        let buffer = Buffer::new(server)?;
+        buffer.load_from_array(sampling_rate, channels, data)?;
+        Ok(buffer)
+    }
+
+    /// Creates a buffer holding the audio in a file, resampled to the sampling rate of the server. This happens synchronously.
+    pub fn from_file<M, P: AsRef<Path>>(
+        server: &server::Server<'server, M, B>,
+        path: P,
+    ) -> Result<Buffer<'server, B>> {
+        let buffer = Buffer::new(server)?;
+        buffer.load_from_file(path)?;
+        Ok(buffer)
+    }
+
+    /// Creates a buffer by decoding encoded audio, such as the contents of an Ogg Vorbis file, resampled to the sampling rate of the server.
+    pub fn from_encoded<M>(
+        server: &server::Server<'server, M, B>,
+        data: &[u8],
+    ) -> Result<Buffer<'server, B>> {
+        let buffer = Buffer::new(server)?;
+        buffer.decode_from_array(data)?;
+        Ok(buffer)
+    }
+
+    /// Creates a buffer by decoding encoded audio in Rust rather than with Libaudioverse, so that the result does not depend on the codecs Libaudioverse was built with.
+    /// The format is detected from the data, and must be enabled with its feature: `vorbis`, `flac`, `mp3` or `wav`. See `AudioFormat`.
+    pub fn decode<M>(
+        server: &server::Server<'server, M, B>,
+        data: &[u8],
+    ) -> Result<Buffer<'server, B>> {
+        let decoded = decoders::decode(data)?;
+        Buffer::from_samples(
+            server,
+            decoded.sampling_rate,
+            decoded.channels,
+            &decoded.samples,
+        )
     }
 
-    /// Get the duration of the buffer in seconds.
-    pub fn get_duration(&self) -> Result<f32> {
+    /// Creates a buffer by reading a file and decoding it as `Buffer::decode` does.
+    pub fn decode_file<M, P: AsRef<Path>>(
+        server: &server::Server<'server, M, B>,
+        path: P,
+    ) -> Result<Buffer<'server, B>> {
+        Buffer::decode(server, &fs::read(path)?)
+    }
+
+    /// Wraps a handle which is known to refer to a buffer.
+    pub(crate) fn from_ptr(handle: LavPtr) -> Buffer<'server, B> {
+        Buffer {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    /// Returns the server this buffer was created on.
+    pub fn server(&self) -> Result<server::Server<'server, server::AnyMode, B>> {
+        let mut server_handle: libaudioverse_sys::LavHandle = 0;
+        check(unsafe { libaudioverse_sys::Lav_bufferGetServer(*self.handle, &mut server_handle) })?;
+        Ok(server::Server {
+            handle: LavPtr::new(server_handle)?,
+            lib: PhantomData,
+            mode: PhantomData,
+            brand: PhantomData,
+        })
+    }
+
+    /// Get the duration of the buffer.
+    pub fn get_duration(&self) -> Result<Duration> {
         let mut duration: f32 = 0.0;
-        check(unsafe { libaudioverse_sys::Lav_bufferGetDuration(self.handle, &mut duration) })?;
-        Ok(duration)
+        check(unsafe { libaudioverse_sys::Lav_bufferGetDuration(*self.handle, &mut duration) })?;
+        Ok(Duration::from_secs_f32(duration))
     }
 
     /// Get the length of the specified buffer in samples. The sample rate of a buffer is the sample rate of the server for which that buffer was created.
@@ -32,51 +126,73 @@ impl Buffer {
     pub fn get_length_in_samples(&self) -> Result<i32> {
         let mut samples: i32 = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_bufferGetLengthInSamples(self.handle, &mut samples)
+            libaudioverse_sys::Lav_bufferGetLengthInSamples(*self.handle, &mut samples)
         })?;
         Ok(samples)
     }
 
     /// Takes an encoded array of audio data and decodes it.
-    pub fn decode_from_array(&self, data: &mut [raw::c_char]) -> Result<()> {
+    pub fn decode_from_array(&self, data: &[u8]) -> Result<()> {
+        if data.len() > c_int::MAX as usize {
+            return Err(Error::new(ErrorKind::Range, "The data is too long"));
+        }
         check(unsafe {
             libaudioverse_sys::Lav_bufferDecodeFromArray(
-                self.handle,
-                data.as_mut_ptr(),
-                data.len() as i32,
+                *self.handle,
+                data.as_ptr() as *mut _,
+                data.len() as c_int,
             )
-        })?;
-        Ok(())
+        })
     }
 
-    /// Load from an array of interleaved floats.
-    pub fn load_from_array(
-        &self,
-        sampling_rate: i32,
-        channels: i32,
-        frames: i32,
-        data: &mut [f32],
-    ) -> Result<()> {
+    /// Load from an array of interleaved floats. The length of `data` must be a multiple of `channels`.
+    pub fn load_from_array(&self, sampling_rate: u32, channels: u32, data: &[f32]) -> Result<()> {
+        if channels == 0 || !data.len().is_multiple_of(channels as usize) {
+            return Err(Error::new(
+                ErrorKind::Range,
+                "The data must contain a whole number of frames",
+            ));
+        }
+        let frames = data.len() / channels as usize;
+        if frames > c_int::MAX as usize {
+            return Err(Error::new(ErrorKind::Range, "The data is too long"));
+        }
         check(unsafe {
             libaudioverse_sys::Lav_bufferLoadFromArray(
-                self.handle,
-                sampling_rate,
-                channels,
-                frames,
-                data.as_mut_ptr(),
+                *self.handle,
+                sampling_rate as c_int,
+                channels as c_int,
+                frames as c_int,
+                data.as_ptr() as *mut _,
             )
-        })?;
-        Ok(())
+        })
     }
 
-    /// Loads data into this buffer from a file. The file will be resampled to the sampling rate of the server. This will happen synchronously.
-    pub fn load_from_file(&self, path: &CString) -> Result<()> {
-        check(unsafe { libaudioverse_sys::Lav_bufferLoadFromFile(self.handle, path.as_ptr()) })?;
-        Ok(())
+    /// Loads data into this buffer from a file. The file will be resampled to the sampling rate of the server. This will happen synchronously. Use a `BufferLoader` to load files in the background instead.
+    pub fn load_from_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path_to_cstring(path.as_ref())?;
+        check(unsafe { libaudioverse_sys::Lav_bufferLoadFromFile(*self.handle, path.as_ptr()) })
     }
 
     /// Normalizes the buffer.
     pub fn normalize(&self) -> Result<()> {
-        check(unsafe { libaudioverse_sys::Lav_bufferNormalize(self.handle) })
+        check(unsafe { libaudioverse_sys::Lav_bufferNormalize(*self.handle) })
     }
 }
+
+#[test]
+fn loads_interleaved_samples() {
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new_offline(&lib).unwrap();
+    assert_eq!(
+        Buffer::from_samples(&server, 44100, 2, &[0.0; 3])
+            .err()
+            .unwrap()
+            .kind,
+        ErrorKind::Range
+    );
+    let buffer = Buffer::from_samples(&server, 44100, 2, &[0.0; 88200]).unwrap();
+    assert_eq!(buffer.get_length_in_samples().unwrap(), 44100);
+    assert_eq!(buffer.get_duration().unwrap(), Duration::from_secs(1));
+    assert_eq!(*buffer.server().unwrap().handle, *server.handle);
+}
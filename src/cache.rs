@@ -0,0 +1,259 @@
+//! Caching of buffers, so that assets are only loaded once.
+use buffer::Buffer;
+use check;
+use libaudioverse_sys;
+use server::{Server, Unbranded};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::os::raw::c_int;
+use std::path::{Path, PathBuf};
+use Result;
+
+type Loader<'server, K, B> = FnMut(&K) -> Result<Buffer<'server, B>> + 'server;
+
+struct Entry<'server, B> {
+    buffer: Buffer<'server, B>,
+    samples: u64,
+    last_used: u64,
+    pinned: bool,
+}
+
+/// A cache of buffers keyed by path or by any other asset id, which loads each asset once and keeps the total size of the buffers it holds within a budget.
+///
+/// Sizes are measured with `Buffer::get_length_in_samples`. When the cache grows past its capacity, the least recently used buffers are evicted, except for buffers which are pinned or in use.
+/// A buffer is in use while a clone of it obtained from the cache is alive. Evicting a buffer only drops the cache's reference to it, so a node which is still reading it keeps its audio data alive.
+///
+/// Assigning a buffer to a node, for example with `BufferNode::buffer`, does not count as using it: Libaudioverse does not report which buffers nodes hold, so only the application's own clones are seen. A buffer which is playing but no longer held by the application can be evicted, after which the next `get` for its key loads it again and both copies take up memory. Keep the clone returned by `get` for as long as a node plays it, or `pin` the buffer.
+/// If nothing can be evicted, the cache is allowed to grow past its capacity until buffers stop being used.
+pub struct BufferCache<'server, K = PathBuf, B = Unbranded> {
+    loader: Box<Loader<'server, K, B>>,
+    entries: HashMap<K, Entry<'server, B>>,
+    capacity: u64,
+    size: u64,
+    clock: u64,
+}
+
+impl<'server, K: Hash + Eq, B> BufferCache<'server, K, B> {
+    /// Creates a cache which holds up to `capacity` samples, and calls `loader` to load the buffer for a key which isn't cached.
+    pub fn new<F>(capacity: u64, loader: F) -> BufferCache<'server, K, B>
+    where
+        F: 'server + FnMut(&K) -> Result<Buffer<'server, B>>,
+    {
+        BufferCache {
+            loader: Box::new(loader),
+            entries: HashMap::new(),
+            capacity,
+            size: 0,
+            clock: 0,
+        }
+    }
+
+    /// Returns the buffer for `key`, loading it if it isn't cached.
+    pub fn get<Q>(&mut self, key: &Q) -> Result<Buffer<'server, B>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + ToOwned<Owned = K>,
+    {
+        self.entry(key).map(|entry| entry.buffer.clone())
+    }
+
+    /// Loads every asset in `keys` which isn't cached yet, stopping at the first one which fails to load.
+    pub fn preload<'k, Q, I>(&mut self, keys: I) -> Result<()>
+    where
+        K: Borrow<Q>,
+        Q: 'k + ?Sized + Hash + Eq + ToOwned<Owned = K>,
+        I: IntoIterator<Item = &'k Q>,
+    {
+        for key in keys {
+            self.entry(key)?;
+        }
+        Ok(())
+    }
+
+    /// Loads the buffer for `key` if it isn't cached, and keeps it from being evicted until it is unpinned.
+    pub fn pin<Q>(&mut self, key: &Q) -> Result<Buffer<'server, B>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + ToOwned<Owned = K>,
+    {
+        self.entry(key).map(|entry| {
+            entry.pinned = true;
+            entry.buffer.clone()
+        })
+    }
+
+    /// Allows the buffer for `key` to be evicted again, returning whether it was pinned.
+    pub fn unpin<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let was_pinned = match self.entries.get_mut(key) {
+            Some(entry) => {
+                let was_pinned = entry.pinned;
+                entry.pinned = false;
+                was_pinned
+            }
+            None => false,
+        };
+        self.evict(0);
+        was_pinned
+    }
+
+    /// Removes the buffer for `key` from the cache regardless of whether it is pinned or in use, returning it if it was cached.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<Buffer<'server, B>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.entries.remove(key).map(|entry| {
+            self.size -= entry.samples;
+            entry.buffer
+        })
+    }
+
+    /// Returns whether the buffer for `key` is cached.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.entries.contains_key(key)
+    }
+
+    /// Returns the number of cached buffers.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the total length in samples of the cached buffers.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the number of samples the cache may hold.
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Changes the number of samples the cache may hold, evicting buffers if it now holds too many.
+    pub fn set_capacity(&mut self, capacity: u64) {
+        self.capacity = capacity;
+        self.evict(0);
+    }
+
+    fn entry<Q>(&mut self, key: &Q) -> Result<&mut Entry<'server, B>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + ToOwned<Owned = K>,
+    {
+        self.clock += 1;
+        if !self.entries.contains_key(key) {
+            let key = key.to_owned();
+            let buffer = (self.loader)(&key)?;
+            let samples = buffer.get_length_in_samples()? as u64;
+            self.size += samples;
+            self.entries.insert(
+                key,
+                Entry {
+                    buffer,
+                    samples,
+                    last_used: self.clock,
+                    pinned: false,
+                },
+            );
+            let clock = self.clock;
+            self.evict(clock);
+        }
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = self.clock;
+                Ok(entry)
+            }
+            None => unreachable!("the buffer which was just used is never evicted"),
+        }
+    }
+
+    /// Evicts the least recently used buffers until the cache fits within its capacity or no more buffers can be evicted. The buffer last used at `spare` is kept.
+    fn evict(&mut self, spare: u64) {
+        if self.size <= self.capacity {
+            return;
+        }
+        let mut candidates: Vec<&Entry<B>> = self
+            .entries
+            .values()
+            .filter(|entry| !entry.pinned && entry.last_used != spare && !in_use(&entry.buffer))
+            .collect();
+        candidates.sort_by_key(|entry| entry.last_used);
+        let mut size = self.size;
+        // no two entries were last used at the same time, so this identifies the evicted ones
+        let mut evicted = Vec::new();
+        for entry in candidates {
+            if size <= self.capacity {
+                break;
+            }
+            size -= entry.samples;
+            evicted.push(entry.last_used);
+        }
+        self.entries
+            .retain(|_, entry| !evicted.contains(&entry.last_used));
+        self.size = size;
+    }
+}
+
+impl<'server, B> BufferCache<'server, PathBuf, B> {
+    /// Creates a cache which holds up to `capacity` samples, and loads files with `Buffer::from_file`.
+    pub fn for_files<M>(
+        server: &'server Server<M, B>,
+        capacity: u64,
+    ) -> BufferCache<'server, PathBuf, B> {
+        BufferCache::new(capacity, move |path: &PathBuf| {
+            Buffer::from_file(server, Path::new(path))
+        })
+    }
+}
+
+/// Returns whether the application still holds a clone of a cached buffer. If the reference count can't be read, the buffer is assumed to be in use.
+/// References held by nodes the buffer is assigned to are not counted, as Libaudioverse only counts the handles given to the application.
+fn in_use<B>(buffer: &Buffer<B>) -> bool {
+    let mut ref_count: c_int = 0;
+    check(unsafe { libaudioverse_sys::Lav_handleGetRefCount(*buffer.handle, &mut ref_count) })
+        .map_or(true, |()| ref_count > 1)
+}
+
+#[test]
+fn evicts_least_recently_used_buffers() {
+    use Libaudioverse;
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new_offline(&lib).unwrap();
+    let sampling_rate = server.get_sampling_rate().unwrap();
+    // each asset is a second of silence
+    let mut cache = BufferCache::new(u64::from(sampling_rate) * 2, |_: &u32| {
+        Buffer::from_samples(
+            &server,
+            sampling_rate,
+            1,
+            &vec![0.0; sampling_rate as usize],
+        )
+    });
+    cache.pin(&1).unwrap();
+    let held = cache.get(&2).unwrap();
+    cache.get(&3).unwrap();
+    assert_eq!(cache.len(), 3);
+    cache.get(&4).unwrap();
+    // 1 is pinned and 2 is held, so 3 goes first
+    assert!(!cache.contains(&3));
+    assert_eq!(cache.size(), u64::from(sampling_rate) * 3);
+    drop(held);
+    assert!(cache.unpin(&1));
+    assert!(!cache.contains(&1));
+    assert!(cache.contains(&2));
+    assert_eq!(cache.len(), 2);
+}
@@ -0,0 +1,76 @@
+//! Shared machinery for handing Rust closures to Libaudioverse as callbacks.
+//!
+//! Libaudioverse calls callbacks from its own threads, so every closure must be `Send + 'static`. Closures are boxed and passed to Libaudioverse as userdata, and the trampolines which receive that userdata catch panics so that they never unwind into C. The boxes themselves are owned by the registry, which frees them when the object they were registered on is destroyed.
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of closures which are being called, so that closures which have been replaced are not freed while one may still be running.
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Owns a closure which has been registered with Libaudioverse.
+/// Dropping this frees the closure, so the registration must be replaced or cleared first.
+pub(crate) struct Callback<F: ?Sized> {
+    closure: *mut Box<F>,
+}
+
+impl<F: ?Sized> Callback<F> {
+    pub(crate) fn new(closure: Box<F>) -> Callback<F> {
+        Callback {
+            closure: Box::into_raw(Box::new(closure)),
+        }
+    }
+
+    /// Returns the pointer to register with Libaudioverse as userdata.
+    pub(crate) fn userdata(&self) -> *mut c_void {
+        self.closure as *mut c_void
+    }
+}
+
+impl<F: ?Sized> Drop for Callback<F> {
+    fn drop(&mut self) {
+        unsafe {
+            drop(Box::from_raw(self.closure));
+        }
+    }
+}
+
+// The closure is only ever reached through the userdata pointer, by the trampolines, which require `F: Send`.
+unsafe impl<F: ?Sized + Send> Send for Callback<F> {}
+
+/// Calls the closure registered with `userdata`.
+/// Panics are caught so that they do not unwind across the FFI boundary; `None` is returned if the closure panicked.
+pub(crate) unsafe fn invoke<F, R, C>(userdata: *mut c_void, call: C) -> Option<R>
+where
+    F: ?Sized,
+    C: FnOnce(&mut F) -> R,
+{
+    let closure = &mut **(userdata as *mut Box<F>);
+    RUNNING.fetch_add(1, Ordering::SeqCst);
+    let result = panic::catch_unwind(AssertUnwindSafe(move || call(closure))).ok();
+    RUNNING.fetch_sub(1, Ordering::SeqCst);
+    result
+}
+
+/// Returns whether any closure is being called.
+pub(crate) fn running() -> bool {
+    RUNNING.load(Ordering::SeqCst) != 0
+}
+
+#[test]
+fn invoke_catches_panics() {
+    type Doubler = FnMut(i32) -> i32 + Send;
+    let callback: Callback<Doubler> = Callback::new(Box::new(|x| {
+        assert!(x >= 0, "negative input");
+        x * 2
+    }));
+    let userdata = callback.userdata();
+    assert_eq!(
+        unsafe { invoke::<Doubler, _, _>(userdata, |f| f(21)) },
+        Some(42)
+    );
+    assert_eq!(
+        unsafe { invoke::<Doubler, _, _>(userdata, |f| f(-1)) },
+        None
+    );
+}
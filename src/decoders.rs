@@ -0,0 +1,222 @@
+//! Decoding of encoded audio in Rust, so that loading assets does not depend on the codecs Libaudioverse was built with. Each format requires its own feature.
+#[cfg(feature = "flac")]
+use claxon;
+#[cfg(feature = "wav")]
+use hound;
+#[cfg(feature = "vorbis")]
+use lewton;
+#[cfg(feature = "mp3")]
+use minimp3;
+#[cfg(any(feature = "flac", feature = "mp3", feature = "vorbis", feature = "wav"))]
+use std::io::Cursor;
+use {Error, ErrorKind, Result};
+
+/// A container format which can be decoded by `Buffer::decode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AudioFormat {
+    /// Ogg Vorbis, which requires the `vorbis` feature.
+    Vorbis,
+    /// FLAC, which requires the `flac` feature.
+    Flac,
+    /// MPEG layer III, which requires the `mp3` feature.
+    Mp3,
+    /// RIFF WAVE, which requires the `wav` feature. That feature also enables `Server::export_wav`.
+    Wav,
+}
+
+impl AudioFormat {
+    /// Determines the format of encoded audio from its first few bytes, returning `None` if it isn't recognized.
+    pub fn detect(data: &[u8]) -> Option<AudioFormat> {
+        if data.starts_with(b"OggS") {
+            Some(AudioFormat::Vorbis)
+        } else if data.starts_with(b"fLaC") {
+            Some(AudioFormat::Flac)
+        } else if data.starts_with(b"RIFF") && data.len() >= 12 && &data[8..12] == b"WAVE" {
+            Some(AudioFormat::Wav)
+        } else if data.starts_with(b"ID3")
+            || (data.len() >= 2 && data[0] == 0xff && data[1] & 0xe0 == 0xe0 && data[1] & 0x06 != 0)
+        {
+            // files without an ID3 tag start with the sync word of the first frame
+            // AAC ADTS headers share the sync word, but their layer bits are 0, which MPEG audio reserves
+            Some(AudioFormat::Mp3)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the feature which enables decoding this format is enabled.
+    pub fn is_supported(self) -> bool {
+        match self {
+            AudioFormat::Vorbis => cfg!(feature = "vorbis"),
+            AudioFormat::Flac => cfg!(feature = "flac"),
+            AudioFormat::Mp3 => cfg!(feature = "mp3"),
+            AudioFormat::Wav => cfg!(feature = "wav"),
+        }
+    }
+
+    /// Returns the name of the feature which enables decoding this format.
+    fn feature(self) -> &'static str {
+        match self {
+            AudioFormat::Vorbis => "vorbis",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Wav => "wav",
+        }
+    }
+}
+
+/// Decoded audio, ready to be loaded into a buffer.
+pub(crate) struct Decoded {
+    pub sampling_rate: u32,
+    pub channels: u32,
+    /// Interleaved samples.
+    pub samples: Vec<f32>,
+}
+
+/// Decodes `data`, choosing a decoder by its format.
+pub(crate) fn decode(data: &[u8]) -> Result<Decoded> {
+    let format = match AudioFormat::detect(data) {
+        Some(format) => format,
+        None => {
+            return Err(Error::new(
+                ErrorKind::File,
+                "The data is not in a recognized audio format",
+            ))
+        }
+    };
+    match format {
+        #[cfg(feature = "vorbis")]
+        AudioFormat::Vorbis => decode_vorbis(data),
+        #[cfg(feature = "flac")]
+        AudioFormat::Flac => decode_flac(data),
+        #[cfg(feature = "mp3")]
+        AudioFormat::Mp3 => decode_mp3(data),
+        #[cfg(feature = "wav")]
+        AudioFormat::Wav => decode_wav(data),
+        #[allow(unreachable_patterns)]
+        format => Err(Error::new(
+            ErrorKind::File,
+            &format!(
+                "Decoding {:?} requires the `{}` feature",
+                format,
+                format.feature()
+            ),
+        )),
+    }
+}
+
+/// Converts the error of a decoder.
+#[cfg(any(feature = "flac", feature = "mp3", feature = "vorbis"))]
+fn decoder_error<E: ::std::fmt::Display>(e: E) -> Error {
+    Error::new(ErrorKind::File, &e.to_string())
+}
+
+#[cfg(feature = "vorbis")]
+fn decode_vorbis(data: &[u8]) -> Result<Decoded> {
+    let mut reader =
+        lewton::inside_ogg::OggStreamReader::new(Cursor::new(data)).map_err(decoder_error)?;
+    let mut samples = Vec::new();
+    while let Some(packet) = reader.read_dec_packet_itl().map_err(decoder_error)? {
+        samples.extend(packet.into_iter().map(|s| f32::from(s) / 32768.0));
+    }
+    Ok(Decoded {
+        sampling_rate: reader.ident_hdr.audio_sample_rate,
+        channels: u32::from(reader.ident_hdr.audio_channels),
+        samples,
+    })
+}
+
+#[cfg(feature = "flac")]
+fn decode_flac(data: &[u8]) -> Result<Decoded> {
+    let mut reader = claxon::FlacReader::new(Cursor::new(data)).map_err(decoder_error)?;
+    let info = reader.streaminfo();
+    let scale = 1.0 / (1u64 << (info.bits_per_sample - 1)) as f32;
+    let mut samples = Vec::new();
+    for sample in reader.samples() {
+        samples.push(sample.map_err(decoder_error)? as f32 * scale);
+    }
+    Ok(Decoded {
+        sampling_rate: info.sample_rate,
+        channels: info.channels,
+        samples,
+    })
+}
+
+#[cfg(feature = "mp3")]
+fn decode_mp3(data: &[u8]) -> Result<Decoded> {
+    let mut decoder = minimp3::Decoder::new(Cursor::new(data));
+    let mut sampling_rate = 0;
+    let mut channels = 0;
+    let mut samples = Vec::new();
+    loop {
+        match decoder.next_frame() {
+            Ok(frame) => {
+                // the stream is assumed not to change format part of the way through, which Libaudioverse couldn't represent anyway
+                sampling_rate = frame.sample_rate as u32;
+                channels = frame.channels as u32;
+                samples.extend(frame.data.into_iter().map(|s| f32::from(s) / 32768.0));
+            }
+            Err(minimp3::Error::Eof) => break,
+            Err(minimp3::Error::SkippedData) => continue,
+            Err(e) => return Err(decoder_error(e)),
+        }
+    }
+    if channels == 0 {
+        return Err(Error::new(
+            ErrorKind::File,
+            "The data does not contain any MP3 frames",
+        ));
+    }
+    Ok(Decoded {
+        sampling_rate,
+        channels,
+        samples,
+    })
+}
+
+#[cfg(feature = "wav")]
+fn decode_wav(data: &[u8]) -> Result<Decoded> {
+    let reader = hound::WavReader::new(Cursor::new(data))?;
+    let spec = reader.spec();
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .into_samples::<f32>()
+            .collect::<::std::result::Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|s| s.map(|s| s as f32 * scale))
+                .collect::<::std::result::Result<_, _>>()?
+        }
+    };
+    Ok(Decoded {
+        sampling_rate: spec.sample_rate,
+        channels: u32::from(spec.channels),
+        samples,
+    })
+}
+
+#[test]
+fn detects_formats_by_magic_bytes() {
+    assert_eq!(
+        AudioFormat::detect(b"OggS\0\x02"),
+        Some(AudioFormat::Vorbis)
+    );
+    assert_eq!(
+        AudioFormat::detect(b"fLaC\0\0\0\x22"),
+        Some(AudioFormat::Flac)
+    );
+    assert_eq!(AudioFormat::detect(b"ID3\x04\0"), Some(AudioFormat::Mp3));
+    assert_eq!(
+        AudioFormat::detect(&[0xff, 0xfb, 0x90]),
+        Some(AudioFormat::Mp3)
+    );
+    assert_eq!(
+        AudioFormat::detect(b"RIFF\x24\0\0\0WAVEfmt "),
+        Some(AudioFormat::Wav)
+    );
+    assert_eq!(AudioFormat::detect(b"RIFF\x24\0\0\0AVI "), None);
+    assert_eq!(AudioFormat::detect(&[0xff, 0xf1, 0x50]), None);
+    assert_eq!(AudioFormat::detect(b""), None);
+}
@@ -0,0 +1,58 @@
+//! Audio output devices.
+use super::libaudioverse_sys;
+use check;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_uint};
+use std::ptr;
+use {Libaudioverse, Result};
+
+/// An audio output device, as returned by `devices`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Device {
+    /// A human-readable name for the device, suitable for showing to users.
+    pub name: String,
+    /// The string identifying the device to `Server::set_output_device_details`. Identifiers are only stable for as long as the device stays plugged in.
+    pub identifier: String,
+    /// The maximum number of channels the device supports.
+    pub channels: u32,
+}
+
+/// Returns the audio output devices present on the system, in the order the audio backend reports them.
+/// Libaudioverse must be initialized to query devices, which borrowing the `Libaudioverse` instance guarantees.
+pub fn devices(_lib: &Libaudioverse) -> Result<Vec<Device>> {
+    let mut count: c_uint = 0;
+    check(unsafe { libaudioverse_sys::Lav_deviceGetCount(&mut count) })?;
+    (0..count)
+        .map(|index| {
+            let mut channels: c_uint = 0;
+            check(unsafe { libaudioverse_sys::Lav_deviceGetChannels(index, &mut channels) })?;
+            Ok(Device {
+                name: device_string(index, libaudioverse_sys::Lav_deviceGetName)?,
+                identifier: device_string(index, libaudioverse_sys::Lav_deviceGetIdentifierString)?,
+                channels,
+            })
+        })
+        .collect()
+}
+
+/// Calls one of the functions returning a string describing a device, copying the string and freeing the original.
+fn device_string(
+    index: c_uint,
+    getter: unsafe extern "C" fn(c_uint, *mut *mut c_char) -> libaudioverse_sys::LavError,
+) -> Result<String> {
+    let mut raw: *mut c_char = ptr::null_mut();
+    check(unsafe { getter(index, &mut raw) })?;
+    unsafe {
+        let string = CStr::from_ptr(raw).to_string_lossy().into_owned();
+        libaudioverse_sys::Lav_free(raw as *mut _);
+        Ok(string)
+    }
+}
+
+#[test]
+fn lists_devices() {
+    let lib = Libaudioverse::new().unwrap();
+    for device in devices(&lib).unwrap() {
+        assert!(!device.identifier.is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+//! Errors reported by Libaudioverse.
+use libaudioverse_sys;
+use std::error;
+use std::ffi::{CStr, NulError};
+use std::fmt;
+use std::io;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::result;
+
+/// The cause of an error. Each variant corresponds to one of Libaudioverse's error codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Something went wrong. This error indicates that we couldn't figure out what.
+    Unknown,
+    /// Attempt to use Libaudioverse without initializing it.
+    NotInitialized,
+    /// Attempt to get a property of the wrong type.
+    TypeMismatch,
+    /// An attempt to access a property which does not exist on the specified node.
+    InvalidProperty,
+    /// A null pointer was passed into Libaudioverse in a context where null pointers are not allowed.
+    NullPointer,
+    /// Libaudioverse tried to allocate memory, but could not.
+    Memory,
+    /// Attempt to free a pointer that Libaudioverse doesn't know about.
+    InvalidPointer,
+    /// A value passed in as a handle is not currently a valid handle.
+    InvalidHandle,
+    /// A function parameter is not within a valid range. This could be setting property values outside their range, accessing inputs and outputs that do not exist, or any of a variety of other range error conditions.
+    Range,
+    /// The audio subsystem could not be initialized.
+    CannotInitAudio,
+    /// Attempt to use an I/O device that doesn't exist. In addition to being caused by your code, this can happen if the user unplugs the device.
+    NoSuchDevice,
+    /// Represents a miscellaneous file error.
+    File,
+    /// Libaudioverse could not find a specified file.
+    FileNotFound,
+    /// An HRTF database is invalid or corrupt.
+    HrtfInvalid,
+    /// An attempt was made to relate two objects from different servers. This could be assigning to buffer properties, connecting nodes, or any other such condition.
+    CannotCrossServers,
+    /// The requested operation would cause a cycle in the graph of nodes that need processing.
+    CausesCycle,
+    /// Attempt to set a read-only property.
+    PropertyIsReadOnly,
+    /// An automator is scheduled to run at the same time as another automator, and these automators conflict in some manner.
+    OverlappingAutomators,
+    /// Attempt to connect a node to a property which cannot be automated.
+    CannotConnectToProperty,
+    /// Indicates an attempt to modify a buffer while something is reading its data.
+    BufferInUse,
+    /// An internal error in Libaudioverse. If you see this error, it's a bug.
+    Internal,
+}
+
+impl ErrorKind {
+    /// Returns the kind corresponding to a Libaudioverse error code. Codes this crate does not know about map to `ErrorKind::Unknown`.
+    pub fn from_code(code: libaudioverse_sys::LavError) -> ErrorKind {
+        match code {
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_NOT_INITIALIZED => ErrorKind::NotInitialized,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_TYPE_MISMATCH => ErrorKind::TypeMismatch,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INVALID_PROPERTY => ErrorKind::InvalidProperty,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_NULL_POINTER => ErrorKind::NullPointer,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_MEMORY => ErrorKind::Memory,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INVALID_POINTER => ErrorKind::InvalidPointer,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INVALID_HANDLE => ErrorKind::InvalidHandle,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_RANGE => ErrorKind::Range,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_CANNOT_INIT_AUDIO => ErrorKind::CannotInitAudio,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_NO_SUCH_DEVICE => ErrorKind::NoSuchDevice,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_FILE => ErrorKind::File,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_FILE_NOT_FOUND => ErrorKind::FileNotFound,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_HRTF_INVALID => ErrorKind::HrtfInvalid,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_CANNOT_CROSS_SERVERS => {
+                ErrorKind::CannotCrossServers
+            }
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_CAUSES_CYCLE => ErrorKind::CausesCycle,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_PROPERTY_IS_READ_ONLY => {
+                ErrorKind::PropertyIsReadOnly
+            }
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_OVERLAPPING_AUTOMATORS => {
+                ErrorKind::OverlappingAutomators
+            }
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_CANNOT_CONNECT_TO_PROPERTY => {
+                ErrorKind::CannotConnectToProperty
+            }
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_BUFFER_IN_USE => ErrorKind::BufferInUse,
+            libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INTERNAL => ErrorKind::Internal,
+            _ => ErrorKind::Unknown,
+        }
+    }
+
+    /// Returns the Libaudioverse error code corresponding to this kind.
+    pub fn code(self) -> libaudioverse_sys::LavError {
+        match self {
+            ErrorKind::Unknown => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_UNKNOWN,
+            ErrorKind::NotInitialized => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_NOT_INITIALIZED,
+            ErrorKind::TypeMismatch => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_TYPE_MISMATCH,
+            ErrorKind::InvalidProperty => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INVALID_PROPERTY,
+            ErrorKind::NullPointer => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_NULL_POINTER,
+            ErrorKind::Memory => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_MEMORY,
+            ErrorKind::InvalidPointer => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INVALID_POINTER,
+            ErrorKind::InvalidHandle => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INVALID_HANDLE,
+            ErrorKind::Range => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_RANGE,
+            ErrorKind::CannotInitAudio => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_CANNOT_INIT_AUDIO,
+            ErrorKind::NoSuchDevice => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_NO_SUCH_DEVICE,
+            ErrorKind::File => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_FILE,
+            ErrorKind::FileNotFound => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_FILE_NOT_FOUND,
+            ErrorKind::HrtfInvalid => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_HRTF_INVALID,
+            ErrorKind::CannotCrossServers => {
+                libaudioverse_sys::Lav_ERRORS_Lav_ERROR_CANNOT_CROSS_SERVERS
+            }
+            ErrorKind::CausesCycle => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_CAUSES_CYCLE,
+            ErrorKind::PropertyIsReadOnly => {
+                libaudioverse_sys::Lav_ERRORS_Lav_ERROR_PROPERTY_IS_READ_ONLY
+            }
+            ErrorKind::OverlappingAutomators => {
+                libaudioverse_sys::Lav_ERRORS_Lav_ERROR_OVERLAPPING_AUTOMATORS
+            }
+            ErrorKind::CannotConnectToProperty => {
+                libaudioverse_sys::Lav_ERRORS_Lav_ERROR_CANNOT_CONNECT_TO_PROPERTY
+            }
+            ErrorKind::BufferInUse => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_BUFFER_IN_USE,
+            ErrorKind::Internal => libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INTERNAL,
+        }
+    }
+}
+
+/// An error raised either by Libaudioverse or by these bindings.
+#[derive(Clone, Debug)]
+pub struct Error {
+    /// What went wrong.
+    pub kind: ErrorKind,
+    /// A human-readable description of the error.
+    pub message: String,
+    /// The Libaudioverse source file which raised the error. This is `None` for errors raised by these bindings.
+    pub file: Option<String>,
+    /// The line in `file` which raised the error.
+    pub line: Option<i32>,
+}
+
+impl Error {
+    /// Creates an error which did not originate from Libaudioverse.
+    pub(crate) fn new(kind: ErrorKind, message: &str) -> Error {
+        Error {
+            kind,
+            message: message.to_string(),
+            file: None,
+            line: None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        // Displaying an `Error` displays the message from libaudioverse, followed by where it was raised if known
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{} ({}:{})", self.message, file, line),
+            _ => self.message.fmt(f),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<NulError> for Error {
+    /// Strings passed to Libaudioverse cannot contain NUL bytes.
+    fn from(_: NulError) -> Error {
+        Error::new(ErrorKind::Range, "The string contains a NUL byte")
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        let kind = match e.kind() {
+            io::ErrorKind::NotFound => ErrorKind::FileNotFound,
+            _ => ErrorKind::File,
+        };
+        Error::new(kind, &e.to_string())
+    }
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+/// Converts a Libaudioverse error code into a `Result`, attaching the message and source location of the last error that happened on this thread.
+pub(crate) fn check(code: libaudioverse_sys::LavError) -> Result<()> {
+    if code == libaudioverse_sys::Lav_ERRORS_Lav_ERROR_NONE {
+        return Ok(());
+    }
+
+    let mut message: *const c_char = ptr::null();
+    let mut file: *const c_char = ptr::null();
+    let mut line: c_int = 0;
+    unsafe {
+        // The returned pointers are valid until another error occurs on this thread.
+        libaudioverse_sys::Lav_errorGetMessage(&mut message);
+        libaudioverse_sys::Lav_errorGetFile(&mut file);
+        libaudioverse_sys::Lav_errorGetLine(&mut line);
+
+        Err(Error {
+            kind: ErrorKind::from_code(code),
+            message: string_from_ptr(message),
+            file: if file.is_null() {
+                None
+            } else {
+                Some(string_from_ptr(file))
+            },
+            line: if file.is_null() { None } else { Some(line) },
+        })
+    }
+}
+
+unsafe fn string_from_ptr(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+#[test]
+fn error_kinds_round_trip_through_codes() {
+    for code in 0..21 {
+        assert_eq!(ErrorKind::from_code(code).code(), code.max(1));
+    }
+    assert_eq!(
+        ErrorKind::from_code(libaudioverse_sys::Lav_ERRORS_Lav_ERROR_INTERNAL),
+        ErrorKind::Internal
+    );
+}
@@ -0,0 +1,163 @@
+//! Exporting the output of offline servers to WAV files. This requires the `wav` feature, which also enables decoding WAV files with `Buffer::decode`.
+use hound;
+use server::{Offline, Server};
+use std::path::Path;
+use std::time::Duration;
+use {Error, ErrorKind, Result};
+
+/// The format samples are stored in by `Server::export_wav`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WavFormat {
+    /// 16-bit integers, as used by CDs.
+    Int16,
+    /// 24-bit integers.
+    Int24,
+    /// 32-bit floats, which store the output of Libaudioverse without any loss.
+    Float32,
+}
+
+/// Options for `Server::export_wav`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WavOptions {
+    /// The number of channels to write.
+    pub channels: u32,
+    /// The format to store samples in.
+    pub format: WavFormat,
+    /// Whether to add triangular dither when writing `WavFormat::Int16`, which turns the distortion caused by rounding into low-level noise. It has no effect on other formats.
+    pub dither: bool,
+    /// Whether to convert the output of the server to `channels` channels with Libaudioverse's mixing matrices.
+    pub apply_mixing_matrix: bool,
+}
+
+impl Default for WavOptions {
+    /// Stereo 16-bit audio with dither.
+    fn default() -> WavOptions {
+        WavOptions {
+            channels: 2,
+            format: WavFormat::Int16,
+            dither: true,
+            apply_mixing_matrix: true,
+        }
+    }
+}
+
+impl From<hound::Error> for Error {
+    fn from(e: hound::Error) -> Error {
+        Error::new(ErrorKind::File, &e.to_string())
+    }
+}
+
+impl<'lib, B> Server<'lib, Offline, B> {
+    /// Renders `duration` of audio to a WAV file, rendering it with `get_block` rather than `write_file` so that the format can be chosen.
+    /// `progress` is called after every block with the fraction of the file which has been written, and with 1 once when the file is complete, even if `duration` is zero.
+    /// `options.channels` must be between 1 and 65,535, the most a WAV file can hold.
+    pub fn export_wav<P, F>(
+        &self,
+        path: P,
+        duration: Duration,
+        options: &WavOptions,
+        mut progress: F,
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+        F: FnMut(f64),
+    {
+        if options.channels == 0 || options.channels > u32::from(u16::MAX) {
+            return Err(Error::new(
+                ErrorKind::Range,
+                "A WAV file must have between 1 and 65,535 channels",
+            ));
+        }
+        let sampling_rate = self.get_sampling_rate()?;
+        let spec = hound::WavSpec {
+            channels: options.channels as u16,
+            sample_rate: sampling_rate,
+            bits_per_sample: match options.format {
+                WavFormat::Int16 => 16,
+                WavFormat::Int24 => 24,
+                WavFormat::Float32 => 32,
+            },
+            sample_format: match options.format {
+                WavFormat::Float32 => hound::SampleFormat::Float,
+                _ => hound::SampleFormat::Int,
+            },
+        };
+        let mut writer = hound::WavWriter::create(path, spec)?;
+        let mut dither = Dither::new();
+
+        let total = (duration.as_secs_f64() * sampling_rate as f64).round() as usize
+            * options.channels as usize;
+        let mut block = vec![0.0; self.get_block_size()? as usize * options.channels as usize];
+        let mut written = 0;
+        while written < total {
+            self.get_block(options.channels, options.apply_mixing_matrix, &mut block)?;
+            for &sample in block.iter().take(total - written) {
+                match options.format {
+                    WavFormat::Int16 => {
+                        let noise = if options.dither { dither.next() } else { 0.0 };
+                        writer.write_sample(quantize(sample, 32767.0, noise) as i16)?
+                    }
+                    WavFormat::Int24 => writer.write_sample(quantize(sample, 8_388_607.0, 0.0))?,
+                    WavFormat::Float32 => writer.write_sample(sample)?,
+                }
+            }
+            written = total.min(written + block.len());
+            if written < total {
+                progress(written as f64 / total as f64);
+            }
+        }
+        writer.finalize()?;
+        progress(1.0);
+        Ok(())
+    }
+}
+
+/// Scales a sample to an integer with the given maximum, adding `noise` (in units of the least significant bit) before rounding.
+fn quantize(sample: f32, max: f32, noise: f32) -> i32 {
+    (sample * max + noise).round().max(-max - 1.0).min(max) as i32
+}
+
+/// Generates triangular noise with a peak amplitude of one least significant bit. The generator only needs to be decorrelated from the audio, so a xorshift is sufficient.
+struct Dither {
+    state: u32,
+}
+
+impl Dither {
+    fn new() -> Dither {
+        Dither { state: 0x9E37_79B9 }
+    }
+
+    fn uniform(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state as f32 / u32::MAX as f32
+    }
+
+    fn next(&mut self) -> f32 {
+        self.uniform() - self.uniform()
+    }
+}
+
+#[test]
+fn exports_the_requested_duration() {
+    use Libaudioverse;
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new_offline(&lib).unwrap();
+    let path = ::std::env::temp_dir().join("libaudioverse_export_test.wav");
+    let mut last_progress = 0.0;
+    server
+        .export_wav(
+            &path,
+            Duration::from_millis(500),
+            &WavOptions::default(),
+            |progress| last_progress = progress,
+        )
+        .unwrap();
+    assert_eq!(last_progress, 1.0);
+    let reader = hound::WavReader::open(&path).unwrap();
+    assert_eq!(reader.spec().channels, 2);
+    assert_eq!(reader.duration(), 22050);
+    ::std::fs::remove_file(&path).unwrap();
+}
@@ -49,3 +49,21 @@ impl Deref for LavPtr {
         &(self.handle)
     }
 }
+
+#[test]
+fn clone_and_drop_adjust_reference_count() {
+    fn ref_count(ptr: &LavPtr) -> i32 {
+        let mut count: i32 = 0;
+        check(unsafe { libaudioverse_sys::Lav_handleGetRefCount(**ptr, &mut count) }).unwrap();
+        count
+    }
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    assert_eq!(ref_count(&server.handle), 1);
+    let cloned = server.handle.clone();
+    assert_eq!(ref_count(&server.handle), 2);
+    drop(cloned);
+    assert_eq!(ref_count(&server.handle), 1);
+    drop(server);
+}
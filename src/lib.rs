@@ -3,71 +3,121 @@
 
 #![feature(nll)]
 
+#[cfg(feature = "flac")]
+extern crate claxon;
+#[cfg(feature = "wav")]
+extern crate hound;
+#[cfg(feature = "vorbis")]
+extern crate lewton;
 extern crate libaudioverse_sys;
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
+#[cfg(feature = "mp3")]
+extern crate minimp3;
 
 mod buffer;
+mod cache;
+mod callbacks;
+mod decoders;
+mod device;
+mod error;
+#[cfg(feature = "wav")]
+mod export;
 pub mod lav_ptr;
+mod loader;
+mod logging;
 pub mod nodes;
+mod registry;
 mod server;
 
-use std::error;
-use std::fmt;
-use std::ptr;
-use std::result;
-
-use std::ffi::CStr;
-use std::os::raw::{c_char, c_int};
+use std::ffi::CString;
+use std::os::raw::c_int;
+use std::path::Path;
+use std::sync::Arc;
 
 use self::libaudioverse_sys::*;
 
 #[doc(inline)]
-pub use self::{buffer::Buffer, server::Server};
-
-#[derive(Debug)]
-pub struct Error {
-    pub code: i32,
-    pub message: String,
+pub use self::{
+    buffer::Buffer,
+    cache::BufferCache,
+    decoders::AudioFormat,
+    device::{devices, Device},
+    error::{Error, ErrorKind, Result},
+    loader::{BufferLoader, LoadHandle, LoadStatus},
+    logging::{
+        clear_logging_callback, logging_level, set_logging_callback, set_logging_level,
+        LoggingLevel,
+    },
+    server::{
+        AnyMode, Blocks, Brand, Offline, Realtime, Server, ServerBuilder, ServerLock, Unbranded,
+    },
+};
+#[cfg(feature = "wav")]
+#[doc(inline)]
+pub use self::export::{WavFormat, WavOptions};
+use error::check;
+
+/// An initialized instance of Libaudioverse.
+///
+/// Creating a `Libaudioverse` initializes the library, and dropping it deinitializes it again. Servers borrow the instance they were created from, so the library cannot be deinitialized while anything created through it is still in use.
+/// More than one instance may exist at the same time: the library stays initialized until the last of them is dropped.
+pub struct Libaudioverse {
+    // prevents construction outside of `Libaudioverse::new`
+    _private: (),
 }
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
-        // Displaying an `Error` simply displays the message from libaudioverse
-        self.message.fmt(f)
+impl Libaudioverse {
+    /// Initializes Libaudioverse.
+    /// With the `log` feature enabled, this also starts forwarding Libaudioverse's log messages to the `log` crate, unless a logging callback has already been set.
+    pub fn new() -> Result<Libaudioverse> {
+        // installed first so that initialization itself can be logged
+        #[cfg(feature = "log")]
+        logging::install_default()?;
+        check(unsafe { Lav_initialize() })?;
+        registry::install();
+        Ok(Libaudioverse { _private: () })
     }
-}
 
-impl error::Error for Error {
-    fn description(&self) -> &str {
-        &self.message
+    /// Sets a callback which is called with the raw handle of every object Libaudioverse destroys, including nodes it created on its own.
+    /// This can be used to evict entries keyed by `Node::get_handle` from application caches.
+    /// The callback may be called from any thread, and must not call into Libaudioverse.
+    pub fn set_handle_destroyed_callback<F>(&self, callback: F)
+    where
+        F: 'static + Send + Sync + Fn(LavHandle),
+    {
+        registry::set_hook(Some(Arc::new(callback)));
     }
-}
-
-pub type Result<T> = result::Result<T, Error>;
 
-fn check(code: LavError) -> Result<()> {
-    if code == Lav_ERRORS_Lav_ERROR_NONE {
-        return Ok(());
+    /// Removes the callback set with `set_handle_destroyed_callback`.
+    pub fn clear_handle_destroyed_callback(&self) {
+        registry::set_hook(None);
     }
+}
 
-    let mut msg: *const c_char = ptr::null_mut();
-    unsafe {
-        // Get the message corresponding to the last error that happened on this thread.
-        // The returned pointer is valid until another error occurs.
-        let _error = Lav_errorGetMessage(&mut msg);
-        let message = CStr::from_ptr(msg).to_string_lossy().into_owned();
-
-        Err(Error {
-            code: code as i32,
-            message,
-        })
+impl Drop for Libaudioverse {
+    fn drop(&mut self) {
+        // every server borrows this instance and has been dropped by now, so it is safe to deinitialize.
+        // closures which were retired are freed while Libaudioverse can still handle what they drop
+        registry::collect_garbage();
+        unsafe {
+            Lav_shutdown();
+        }
+        // shutting down destroys every remaining object, whose Rust-side state can now be freed
+        registry::collect_garbage();
     }
 }
 
-/// Initializes Libaudioverse. Failure to do so will result in crashes. You may initialize the library more than once: subsequent initializations do nothing.
-pub fn initialize() -> Result<()> {
-    check(unsafe { Lav_initialize() })
+/// Converts a path into the form Libaudioverse expects. Paths are passed to Libaudioverse as UTF-8, so paths which are not valid UTF-8 are rejected.
+pub(crate) fn path_to_cstring(path: &Path) -> Result<CString> {
+    match path.to_str() {
+        Some(path) => Ok(CString::new(path)?),
+        None => Err(Error::new(ErrorKind::File, "The path is not valid UTF-8")),
+    }
 }
 
+/// Returns whether Libaudioverse is currently initialized, which is the case while at least one `Libaudioverse` instance is alive.
 pub fn is_initialized() -> Result<bool> {
     let mut res: c_int = 0;
 
@@ -75,14 +125,9 @@ pub fn is_initialized() -> Result<bool> {
     Ok(res != 0)
 }
 
-/// Deinitializes Libaudioverse. Failure to do so may lead to crashes, depending on what is or is not created. It is not safe to assume that Libaudioverse will properly clean itself up at process exit unless this function is called. You must deinitialize the library exactly as many times as it has been initialized.
-pub fn shutdown() -> Result<()> {
-    check(unsafe { Lav_shutdown() })
-}
-
 #[test]
 fn initializes_and_shuts_down() {
-    initialize().unwrap();
+    let lib = Libaudioverse::new().unwrap();
     assert!(is_initialized().unwrap());
-    shutdown().unwrap();
+    drop(lib);
 }
@@ -0,0 +1,445 @@
+//! Loading of buffers on background threads.
+use buffer::Buffer;
+use check;
+use decoders::{self, AudioFormat};
+use lav_ptr::LavPtr;
+use libaudioverse_sys;
+use server::{Server, Unbranded};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::Read;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use {Error, ErrorKind, Result};
+
+/// The size of the chunks files are read in, which determines how often progress is reported while reading.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Loads the file of a task on a worker thread. `BufferLoader::new` uses `load`.
+type LoadFn = fn(&LavPtr, &Task, &Fn() -> bool) -> Result<Option<LavPtr>>;
+
+// panics are caught before they can leave a task or the queue half updated, so poisoning is ignored
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The state of a load started with `BufferLoader::load`.
+pub enum LoadStatus<'server, B = Unbranded> {
+    /// The asset is waiting for a worker thread.
+    Queued,
+    /// The asset is being loaded, and the given fraction of the work is done.
+    Loading(f32),
+    /// The asset was loaded into the buffer.
+    Loaded(Buffer<'server, B>),
+    /// The asset could not be loaded.
+    Failed(Error),
+    /// The load was cancelled before it finished.
+    Cancelled,
+}
+
+impl<'server, B> Clone for LoadStatus<'server, B> {
+    fn clone(&self) -> LoadStatus<'server, B> {
+        match *self {
+            LoadStatus::Queued => LoadStatus::Queued,
+            LoadStatus::Loading(progress) => LoadStatus::Loading(progress),
+            LoadStatus::Loaded(ref buffer) => LoadStatus::Loaded(buffer.clone()),
+            LoadStatus::Failed(ref e) => LoadStatus::Failed(e.clone()),
+            LoadStatus::Cancelled => LoadStatus::Cancelled,
+        }
+    }
+}
+
+impl<'server, B> LoadStatus<'server, B> {
+    /// Returns whether the load has finished, whether or not it succeeded.
+    pub fn is_finished(&self) -> bool {
+        !matches!(*self, LoadStatus::Queued | LoadStatus::Loading(_))
+    }
+}
+
+enum State {
+    Queued,
+    Loading(f32),
+    Loaded(LavPtr),
+    Failed(Error),
+    Cancelled,
+}
+
+struct Task {
+    path: PathBuf,
+    state: Mutex<State>,
+    finished: Condvar,
+    cancelled: AtomicBool,
+}
+
+impl Task {
+    fn set_progress(&self, progress: f32) {
+        if let State::Loading(ref mut current) = *lock(&self.state) {
+            *current = progress;
+        }
+    }
+
+    fn finish(&self, state: State) {
+        *lock(&self.state) = state;
+        self.finished.notify_all();
+    }
+}
+
+/// An entry in the queue. Higher priorities come first, and assets with the same priority are loaded in the order they were requested.
+struct Queued {
+    priority: i32,
+    sequence: u64,
+    task: Arc<Task>,
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Queued) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Queued) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Queued) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+struct Queue {
+    tasks: BinaryHeap<Queued>,
+    next_sequence: u64,
+}
+
+struct Shared {
+    server: LavPtr,
+    queue: Mutex<Queue>,
+    available: Condvar,
+    shutting_down: AtomicBool,
+}
+
+/// Loads buffers from files on a pool of worker threads, so that reading, decoding and resampling long files doesn't block the thread which requested them.
+///
+/// Files are decoded in Rust when the feature for their format is enabled (see `AudioFormat`), and by Libaudioverse otherwise. Either way, they are resampled to the sampling rate of the server by the worker.
+/// Dropping the loader cancels every load which hasn't finished, and waits for the worker threads to exit.
+pub struct BufferLoader<'server, B = Unbranded> {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+    server: PhantomData<(&'server Server<'server>, B)>, // buffers cannot outlive the library instance of the server they were created on
+}
+
+impl<'server, B> BufferLoader<'server, B> {
+    /// Creates a loader which loads buffers for `server` on `threads` worker threads.
+    pub fn new<M>(
+        server: &Server<'server, M, B>,
+        threads: usize,
+    ) -> Result<BufferLoader<'server, B>> {
+        BufferLoader::with_load(server, threads, load)
+    }
+
+    fn with_load<M>(
+        server: &Server<'server, M, B>,
+        threads: usize,
+        load: LoadFn,
+    ) -> Result<BufferLoader<'server, B>> {
+        if threads == 0 {
+            return Err(Error::new(
+                ErrorKind::Range,
+                "A loader needs at least one thread",
+            ));
+        }
+        let shared = Arc::new(Shared {
+            server: server.handle.clone(),
+            queue: Mutex::new(Queue {
+                tasks: BinaryHeap::new(),
+                next_sequence: 0,
+            }),
+            available: Condvar::new(),
+            shutting_down: AtomicBool::new(false),
+        });
+        let mut loader = BufferLoader {
+            shared,
+            workers: Vec::with_capacity(threads),
+            server: PhantomData,
+        };
+        for i in 0..threads {
+            let shared = loader.shared.clone();
+            // if spawning fails, dropping the loader stops the workers which were already started
+            let worker = thread::Builder::new()
+                .name(format!("libaudioverse-loader-{}", i))
+                .spawn(move || work(&shared, load))?;
+            loader.workers.push(worker);
+        }
+        Ok(loader)
+    }
+
+    /// Queues a file to be loaded with the default priority of 0.
+    pub fn load<P: Into<PathBuf>>(&self, path: P) -> LoadHandle<'server, B> {
+        self.load_with_priority(path, 0)
+    }
+
+    /// Queues a file to be loaded. Files with higher priorities are loaded first.
+    pub fn load_with_priority<P: Into<PathBuf>>(
+        &self,
+        path: P,
+        priority: i32,
+    ) -> LoadHandle<'server, B> {
+        let task = Arc::new(Task {
+            path: path.into(),
+            state: Mutex::new(State::Queued),
+            finished: Condvar::new(),
+            cancelled: AtomicBool::new(false),
+        });
+        let mut queue = lock(&self.shared.queue);
+        let sequence = queue.next_sequence;
+        queue.next_sequence += 1;
+        queue.tasks.push(Queued {
+            priority,
+            sequence,
+            task: task.clone(),
+        });
+        self.shared.available.notify_one();
+        LoadHandle {
+            task,
+            server: PhantomData,
+        }
+    }
+
+    /// Returns the number of assets waiting for a worker thread, including cancelled ones which haven't been discarded yet.
+    pub fn queued(&self) -> usize {
+        lock(&self.shared.queue).tasks.len()
+    }
+}
+
+impl<'server, B> Drop for BufferLoader<'server, B> {
+    fn drop(&mut self) {
+        {
+            // set while holding the lock, so that no worker can miss it between checking and waiting
+            let mut queue = lock(&self.shared.queue);
+            self.shared
+                .shutting_down
+                .store(true, atomic::Ordering::SeqCst);
+            for queued in queue.tasks.drain() {
+                cancel(&queued.task);
+            }
+        }
+        self.shared.available.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Tracks an asset queued with `BufferLoader::load`.
+pub struct LoadHandle<'server, B = Unbranded> {
+    task: Arc<Task>,
+    server: PhantomData<(&'server Server<'server>, B)>,
+}
+
+impl<'server, B> Clone for LoadHandle<'server, B> {
+    fn clone(&self) -> LoadHandle<'server, B> {
+        LoadHandle {
+            task: self.task.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> LoadHandle<'server, B> {
+    /// Returns the path of the asset.
+    pub fn path(&self) -> &Path {
+        &self.task.path
+    }
+
+    /// Returns the current state of the load without blocking.
+    pub fn status(&self) -> LoadStatus<'server, B> {
+        to_status(&lock(&self.task.state))
+    }
+
+    /// Returns whether the load has finished, whether or not it succeeded.
+    pub fn is_finished(&self) -> bool {
+        self.status().is_finished()
+    }
+
+    /// Blocks until the load has finished, and returns its final state.
+    pub fn wait(&self) -> LoadStatus<'server, B> {
+        let mut state = lock(&self.task.state);
+        loop {
+            let status = to_status(&state);
+            if status.is_finished() {
+                return status;
+            }
+            state = self
+                .task
+                .finished
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Cancels the load. A queued asset is dropped immediately, and one which is being loaded is abandoned at the next opportunity. This has no effect on loads which have finished.
+    pub fn cancel(&self) {
+        cancel(&self.task);
+    }
+}
+
+fn to_status<'server, B>(state: &State) -> LoadStatus<'server, B> {
+    match *state {
+        State::Queued => LoadStatus::Queued,
+        State::Loading(progress) => LoadStatus::Loading(progress),
+        State::Loaded(ref handle) => LoadStatus::Loaded(Buffer::from_ptr(handle.clone())),
+        State::Failed(ref e) => LoadStatus::Failed(e.clone()),
+        State::Cancelled => LoadStatus::Cancelled,
+    }
+}
+
+fn cancel(task: &Task) {
+    task.cancelled.store(true, atomic::Ordering::SeqCst);
+    let mut state = lock(&task.state);
+    // the worker notices loads which have started itself
+    if let State::Queued = *state {
+        *state = State::Cancelled;
+        task.finished.notify_all();
+    }
+}
+
+/// Runs on each worker thread until the loader is dropped.
+/// A load which panics fails, rather than taking the worker down and leaving its task unfinished forever.
+fn work(shared: &Shared, load: LoadFn) {
+    loop {
+        let task = {
+            let mut queue = lock(&shared.queue);
+            loop {
+                if shared.shutting_down.load(atomic::Ordering::SeqCst) {
+                    return;
+                }
+                if let Some(queued) = queue.tasks.pop() {
+                    break queued.task;
+                }
+                queue = shared
+                    .available
+                    .wait(queue)
+                    .unwrap_or_else(|e| e.into_inner());
+            }
+        };
+        {
+            let mut state = lock(&task.state);
+            if let State::Cancelled = *state {
+                continue;
+            }
+            *state = State::Loading(0.0);
+        }
+        let is_cancelled = || {
+            task.cancelled.load(atomic::Ordering::SeqCst)
+                || shared.shutting_down.load(atomic::Ordering::SeqCst)
+        };
+        let loaded = panic::catch_unwind(AssertUnwindSafe(|| {
+            load(&shared.server, &task, &is_cancelled)
+        }));
+        task.finish(match loaded {
+            Ok(Ok(Some(handle))) => State::Loaded(handle),
+            Ok(Ok(None)) => State::Cancelled,
+            Ok(Err(e)) => State::Failed(e),
+            Err(_) => State::Failed(Error::new(ErrorKind::Unknown, "Loading the asset panicked")),
+        });
+    }
+}
+
+/// Loads the file of a task, returning `None` if the task was cancelled.
+fn load(server: &LavPtr, task: &Task, is_cancelled: &Fn() -> bool) -> Result<Option<LavPtr>> {
+    // reading, decoding and resampling are each counted as a third of the work
+    let mut file = File::open(&task.path)?;
+    let length = file.metadata()?.len().max(1);
+    let mut data = Vec::with_capacity(length as usize);
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        if is_cancelled() {
+            return Ok(None);
+        }
+        let read = file.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&chunk[..read]);
+        task.set_progress((data.len() as f32 / length as f32).min(1.0) / 3.0);
+    }
+
+    let mut buffer_handle: libaudioverse_sys::LavHandle = 0;
+    check(unsafe { libaudioverse_sys::Lav_createBuffer(**server, &mut buffer_handle) })?;
+    let buffer: Buffer = Buffer::from_ptr(LavPtr::new(buffer_handle)?);
+    match AudioFormat::detect(&data) {
+        Some(format) if format.is_supported() => {
+            let decoded = decoders::decode(&data)?;
+            drop(data);
+            task.set_progress(2.0 / 3.0);
+            if is_cancelled() {
+                return Ok(None);
+            }
+            buffer.load_from_array(decoded.sampling_rate, decoded.channels, &decoded.samples)?;
+        }
+        // Libaudioverse decodes and resamples in one step
+        _ => buffer.decode_from_array(&data)?,
+    }
+    if is_cancelled() {
+        return Ok(None);
+    }
+    Ok(Some(buffer.handle))
+}
+
+#[test]
+fn loads_files_in_the_background() {
+    use std::time::Duration;
+    use Libaudioverse;
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new_offline(&lib).unwrap();
+    let path = ::std::env::temp_dir().join("libaudioverse_loader_test.wav");
+    server
+        .write_file(&path, 2, Duration::from_secs(1), true)
+        .unwrap();
+    let loader = BufferLoader::new(&server, 2).unwrap();
+    let missing = loader.load(::std::env::temp_dir().join("libaudioverse_missing.wav"));
+    let loaded = loader.load_with_priority(&path, 1);
+    match loaded.wait() {
+        LoadStatus::Loaded(buffer) => assert_eq!(
+            buffer.get_length_in_samples().unwrap() as u32,
+            server.get_sampling_rate().unwrap()
+        ),
+        _ => panic!("the file was not loaded"),
+    }
+    match missing.wait() {
+        LoadStatus::Failed(e) => assert_eq!(e.kind, ErrorKind::FileNotFound),
+        _ => panic!("loading a missing file did not fail"),
+    }
+    drop(loader);
+    ::std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn fails_loads_which_panic() {
+    use Libaudioverse;
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new_offline(&lib).unwrap();
+    let loader = BufferLoader::with_load(&server, 1, |_, _, _| panic!("deliberate panic")).unwrap();
+    let first = loader.load("first.wav");
+    let second = loader.load("second.wav");
+    for handle in &[first, second] {
+        match handle.wait() {
+            LoadStatus::Failed(e) => assert_eq!(e.kind, ErrorKind::Unknown),
+            _ => panic!("a load which panicked did not fail"),
+        }
+    }
+}
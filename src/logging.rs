@@ -0,0 +1,105 @@
+//! Diagnostics logged by Libaudioverse.
+//!
+//! By default, log messages are discarded. A closure can be installed with `set_logging_callback`; alternatively, with the `log` feature enabled, messages are forwarded to the `log` crate under the `libaudioverse` target unless another callback is installed.
+use super::libaudioverse_sys;
+use check;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use Result;
+
+/// The verbosity of Libaudioverse's logging. Each level includes the messages of the levels before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LoggingLevel {
+    /// Logs only critical messages, such as failures to initialize an audio device.
+    Critical = libaudioverse_sys::Lav_LOGGING_LEVELS_Lav_LOGGING_LEVEL_CRITICAL as isize,
+    /// Logs informative messages.
+    Info = libaudioverse_sys::Lav_LOGGING_LEVELS_Lav_LOGGING_LEVEL_INFO as isize,
+    /// Logs everything, which is useful for debugging Libaudioverse itself.
+    Debug = libaudioverse_sys::Lav_LOGGING_LEVELS_Lav_LOGGING_LEVEL_DEBUG as isize,
+    /// Logs nothing.
+    Off = libaudioverse_sys::Lav_LOGGING_LEVELS_Lav_LOGGING_LEVEL_OFF as isize,
+}
+
+impl LoggingLevel {
+    fn from_raw(level: c_int) -> LoggingLevel {
+        if level <= LoggingLevel::Critical as c_int {
+            LoggingLevel::Critical
+        } else if level <= LoggingLevel::Info as c_int {
+            LoggingLevel::Info
+        } else if level <= LoggingLevel::Debug as c_int {
+            LoggingLevel::Debug
+        } else {
+            LoggingLevel::Off
+        }
+    }
+}
+
+type LoggingCallback = Arc<Fn(LoggingLevel, &str) + Send + Sync>;
+
+static CALLBACK: Mutex<Option<LoggingCallback>> = Mutex::new(None);
+
+/// Sets the level of messages which Libaudioverse logs. This may be called before Libaudioverse is initialized, so that initialization can be logged.
+pub fn set_logging_level(level: LoggingLevel) -> Result<()> {
+    check(unsafe { libaudioverse_sys::Lav_setLoggingLevel(level as c_int) })
+}
+
+/// Returns the level of messages which Libaudioverse logs.
+pub fn logging_level() -> Result<LoggingLevel> {
+    let mut level: c_int = 0;
+    check(unsafe { libaudioverse_sys::Lav_getLoggingLevel(&mut level) })?;
+    Ok(LoggingLevel::from_raw(level))
+}
+
+/// Sets the closure which receives Libaudioverse's log messages, replacing any previous one.
+/// The closure is called from a thread owned by Libaudioverse.
+pub fn set_logging_callback<F>(callback: F) -> Result<()>
+where
+    F: 'static + Send + Sync + Fn(LoggingLevel, &str),
+{
+    *CALLBACK.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(callback));
+    check(unsafe { libaudioverse_sys::Lav_setLoggingCallback(Some(logging_handler)) })
+}
+
+/// Stops delivering log messages to the closure set with `set_logging_callback`.
+pub fn clear_logging_callback() -> Result<()> {
+    check(unsafe { libaudioverse_sys::Lav_setLoggingCallback(None) })?;
+    *CALLBACK.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    Ok(())
+}
+
+/// Forwards log messages to the `log` crate, unless the application has already installed its own callback.
+#[cfg(feature = "log")]
+pub(crate) fn install_default() -> Result<()> {
+    if CALLBACK.lock().unwrap_or_else(|e| e.into_inner()).is_some() {
+        return Ok(());
+    }
+    set_logging_callback(|level, message| match level {
+        LoggingLevel::Critical => error!(target: "libaudioverse", "{}", message),
+        LoggingLevel::Info => info!(target: "libaudioverse", "{}", message),
+        LoggingLevel::Debug | LoggingLevel::Off => debug!(target: "libaudioverse", "{}", message),
+    })
+}
+
+/// Handles log messages from Libaudioverse, passing them on to the installed closure.
+unsafe extern "C" fn logging_handler(level: c_int, message: *const c_char) {
+    if message.is_null() {
+        return;
+    }
+    let callback = CALLBACK.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(callback) = callback {
+        let message = CStr::from_ptr(message).to_string_lossy();
+        // panics must not unwind into Libaudioverse
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+            callback(LoggingLevel::from_raw(level), &message)
+        }));
+    }
+}
+
+#[test]
+fn sets_logging_level() {
+    set_logging_level(LoggingLevel::Debug).unwrap();
+    assert_eq!(logging_level().unwrap(), LoggingLevel::Debug);
+    set_logging_level(LoggingLevel::Off).unwrap();
+}
@@ -0,0 +1,154 @@
+use super::super::{libaudioverse_sys, server};
+use super::properties::{BoolProperty, FloatArrayProperty, FloatProperty};
+use super::{Node, NodeType, TypedNode};
+use check;
+use lav_ptr::LavPtr;
+use std::marker::PhantomData;
+use std::os::raw::c_uint;
+use Result;
+
+/// This panner pans for a set of regular speakers, without any additional effects applied.
+/// The speakers are described by the channel map, which defaults to a stereo layout; use `configure_standard_map` for the standard surround sound layouts.
+///
+/// Inputs:
+///
+/// index | channels | description
+/// ------|----------|------------
+/// 0 | 1 | The signal to pan.
+///
+/// Outputs:
+///
+/// index | channels | description
+/// ------|----------|------------
+/// 0 | Depends on the channel map | The panned signal, with one channel per speaker.
+pub struct AmplitudePannerNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
+}
+
+impl<'server, B> Clone for AmplitudePannerNode<'server, B> {
+    fn clone(&self) -> AmplitudePannerNode<'server, B> {
+        AmplitudePannerNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for AmplitudePannerNode<'server, B> {
+    #[doc(hidden)]
+    fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for AmplitudePannerNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::AmplitudePannerNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> AmplitudePannerNode<'server, B> {
+        AmplitudePannerNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
+        self.handle
+    }
+}
+
+impl<'server, B> AmplitudePannerNode<'server, B> {
+    /// Creates a new amplitude panner node, configured for stereo output.
+    pub fn new<M>(
+        server: &server::Server<'server, M, B>,
+    ) -> Result<AmplitudePannerNode<'server, B>> {
+        let mut node_handle: libaudioverse_sys::LavHandle = 0;
+        check(unsafe {
+            libaudioverse_sys::Lav_createAmplitudePannerNode(*server.handle, &mut node_handle)
+        })?;
+        Ok(AmplitudePannerNode {
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
+        })
+    }
+
+    /// Sets the channel map to the standard speaker layout for the given number of channels.
+    /// Libaudioverse knows the layouts for 2, 4, 6 (5.1) and 8 (7.1) channels; other counts are an error.
+    pub fn configure_standard_map(&self, channels: u32) -> Result<()> {
+        check(unsafe {
+            libaudioverse_sys::Lav_amplitudePannerNodeConfigureStandardMap(
+                *self.handle,
+                channels as c_uint,
+            )
+        })
+    }
+
+    /// Returns the azimuth property.
+    ///
+    /// Range: [-INFINITY, INFINITY]
+    ///
+    /// Default value: 0.0
+    ///
+    /// The horizontal angle of the panner in degrees. 0 is straight ahead and positive values are clockwise.
+    pub fn azimuth(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_AZIMUTH,
+        )
+    }
+
+    /// Returns the elevation property.
+    ///
+    /// Range: [-90.0, 90.0]
+    ///
+    /// Default value: 0.0
+    ///
+    /// The vertical angle of the panner in degrees. 0 is horizontal and positive values move upward.
+    pub fn elevation(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_ELEVATION,
+        )
+    }
+
+    /// Returns the channel_map property.
+    ///
+    /// Default value: [-90.0, 90.0]
+    ///
+    /// The angles of the speakers in degrees, in the order of the output channels they are mapped to.
+    /// Use infinity for a channel which should not be given a speaker, such as the LFE.
+    pub fn channel_map(&self) -> FloatArrayProperty<'_, B> {
+        FloatArrayProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_CHANNEL_MAP,
+        )
+    }
+
+    /// Returns the should_crossfade property.
+    ///
+    /// Default value: True
+    ///
+    /// By default, panners crossfade their output. This property allows such functionality to be disabled.
+    pub fn should_crossfade(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_SHOULD_CROSSFADE,
+        )
+    }
+}
+
+#[test]
+fn standard_maps_set_the_channel_map() {
+    use {Libaudioverse, Server};
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    let panner = AmplitudePannerNode::new(&server).unwrap();
+    assert_eq!(panner.channel_map().get().unwrap(), vec![-90.0, 90.0]);
+    panner.configure_standard_map(6).unwrap();
+    assert_eq!(panner.channel_map().len().unwrap(), 6);
+    panner.channel_map().write(0, &[-45.0, 45.0]).unwrap();
+    assert_eq!(panner.channel_map().read(1).unwrap(), 45.0);
+}
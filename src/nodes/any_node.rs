@@ -0,0 +1,132 @@
+use super::super::{libaudioverse_sys, server};
+use super::{
+    AmplitudePannerNode, BufferNode, EnvironmentNode, FileStreamerNode, GainNode, HrtfNode, Node,
+    NodeType, PullNode, PushNode, SourceNode,
+};
+use check;
+use lav_ptr::LavPtr;
+use std::marker::PhantomData;
+use std::result;
+use {Error, ErrorKind, Result};
+
+/// Nodes which are represented by their own wrapper type, allowing an `AnyNode` to be converted back into them.
+pub trait TypedNode<'server, B = server::Unbranded>: Node<B> + Sized {
+    /// The type of the Libaudioverse nodes which this type wraps.
+    const NODE_TYPE: NodeType;
+
+    /// Wraps a handle which is known to refer to a node of type `NODE_TYPE`.
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> Self;
+
+    /// Releases the wrapped handle.
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr;
+}
+
+/// A node of any type.
+/// Use this to store nodes of different types in the same collection, or to wrap raw handles handed out by Libaudioverse. It can be converted back into the wrapper for its type with `downcast`.
+pub struct AnyNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
+}
+
+impl<'server, B> Clone for AnyNode<'server, B> {
+    fn clone(&self) -> AnyNode<'server, B> {
+        AnyNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for AnyNode<'server, B> {
+    #[doc(hidden)]
+    fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> AnyNode<'server, B> {
+    /// Wraps a raw handle to a node.
+    /// It is an error if the handle does not refer to a node, or if that node does not belong to the provided server.
+    pub fn from_raw<M>(
+        server: &server::Server<'server, M, B>,
+        handle: libaudioverse_sys::LavHandle,
+    ) -> Result<AnyNode<'server, B>> {
+        let mut object_type = 0;
+        check(unsafe { libaudioverse_sys::Lav_handleGetType(handle, &mut object_type) })?;
+        if !matches!(NodeType::from_raw(object_type), Some(node_type) if node_type.is_node()) {
+            return Err(Error::new(
+                ErrorKind::TypeMismatch,
+                "The handle does not refer to a node",
+            ));
+        }
+        let mut server_handle: libaudioverse_sys::LavHandle = 0;
+        check(unsafe { libaudioverse_sys::Lav_nodeGetServer(handle, &mut server_handle) })?;
+        if server_handle != *server.handle {
+            return Err(Error::new(
+                ErrorKind::CannotCrossServers,
+                "The node belongs to a different server",
+            ));
+        }
+        Ok(AnyNode {
+            handle: LavPtr::new(handle)?,
+            server: PhantomData,
+        })
+    }
+
+    /// Converts this node into the wrapper for its type, or gives it back if it is of a different type.
+    pub fn downcast<T: TypedNode<'server, B>>(self) -> result::Result<T, AnyNode<'server, B>> {
+        if self.is::<T>() {
+            Ok(T::from_ptr(self.handle))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Returns whether this node can be downcast to `T`.
+    pub fn is<T: TypedNode<'server, B>>(&self) -> bool {
+        self.node_type().ok() == Some(T::NODE_TYPE)
+    }
+}
+
+// implemented for each type rather than for every `TypedNode`, which would overlap with `From<AnyNode>` for a brand defined elsewhere
+macro_rules! impl_from_typed_node {
+    ($($node:ident),*) => {
+        $(
+            impl<'server, B> From<$node<'server, B>> for AnyNode<'server, B> {
+                fn from(node: $node<'server, B>) -> AnyNode<'server, B> {
+                    AnyNode {
+                        handle: node.into_ptr(),
+                        server: PhantomData,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_typed_node!(
+    AmplitudePannerNode,
+    BufferNode,
+    EnvironmentNode,
+    FileStreamerNode,
+    GainNode,
+    HrtfNode,
+    PullNode,
+    PushNode,
+    SourceNode
+);
+
+#[test]
+fn downcasts_to_the_original_type_only() {
+    use super::{GainNode, SourceNode};
+    use {Libaudioverse, Server};
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    let node: AnyNode = GainNode::new(&server, 2).unwrap().into();
+    assert_eq!(node.node_type().unwrap(), NodeType::GainNode);
+    let node = node.downcast::<SourceNode>().err().unwrap();
+    assert!(node.downcast::<GainNode>().is_ok());
+}
@@ -1,7 +1,9 @@
 use super::super::{libaudioverse_sys, server};
 use super::properties::{BoolProperty, BufferProperty, DoubleProperty, IntProperty};
-use super::Node;
+use super::{Node, NodeType, TypedNode};
 use check;
+use lav_ptr::LavPtr;
+use std::marker::PhantomData;
 use Result;
 
 /// This node plays a buffer. The output of this node will have as many channels as the buffer does, so connecting it directly to the server will have the desired effect.
@@ -13,33 +15,63 @@ use Result;
 /// index | channels | description
 /// ------|----------|------------
 /// 0 | Depends on the currently playing buffer. | The output from the buffer being played.
-pub struct BufferNode {
-    handle: libaudioverse_sys::LavHandle,
+pub struct BufferNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
 }
 
-impl Node for BufferNode {
+impl<'server, B> Clone for BufferNode<'server, B> {
+    fn clone(&self) -> BufferNode<'server, B> {
+        BufferNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for BufferNode<'server, B> {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for BufferNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::BufferNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> BufferNode<'server, B> {
+        BufferNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
         self.handle
     }
 }
 
-impl BufferNode {
+impl<'server, B> BufferNode<'server, B> {
     /// Creates a new buffer node.
-    pub fn new(server: &server::Server) -> Result<BufferNode> {
+    pub fn new<M>(server: &server::Server<'server, M, B>) -> Result<BufferNode<'server, B>> {
         let mut node_handle: libaudioverse_sys::LavHandle = 0;
-        check(unsafe { libaudioverse_sys::Lav_createBufferNode(server.handle, &mut node_handle) })?;
+        check(unsafe {
+            libaudioverse_sys::Lav_createBufferNode(*server.handle, &mut node_handle)
+        })?;
         Ok(BufferNode {
-            handle: node_handle,
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
         })
     }
 
     /// Returns the currently playing buffer. Setting this property will reset position.
-    pub fn buffer(&self) -> BufferProperty {
-        BufferProperty {
-            index: libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_BUFFER,
-            node_handle: self.handle,
-        }
+    pub fn buffer(&self) -> BufferProperty<'_, B> {
+        BufferProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_BUFFER,
+        )
     }
 
     /// Returns the ended_count property.
@@ -49,11 +81,11 @@ impl BufferNode {
     /// Default value: 0
     ///
     /// Increments every time the buffer reaches it’s end. If the buffer is not looping, this can be used to determine when the buffer is ended, without using the callback. if the buffer is configured to loop, the counter will count up every time the end of a loop is reached. You can write to this property to reset it.
-    pub fn ended_count(&self) -> IntProperty {
-        IntProperty {
-            index: libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_ENDED_COUNT,
-            node_handle: self.handle,
-        }
+    pub fn ended_count(&self) -> IntProperty<'_> {
+        IntProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_ENDED_COUNT,
+        )
     }
 
     /// Returns the looping property.
@@ -61,11 +93,11 @@ impl BufferNode {
     /// Default value: False
     ///
     /// If true, this node continues playing the same buffer from the beginning after it reaches the end.
-    pub fn looping(&self) -> BoolProperty {
-        BoolProperty {
-            index: libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_LOOPING,
-            node_handle: self.handle,
-        }
+    pub fn looping(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_LOOPING,
+        )
     }
 
     /// Returns the position property.
@@ -75,11 +107,11 @@ impl BufferNode {
     /// Default value: 0.0
     ///
     /// The position of playback, in seconds. The range of this property corresponds to the total duration of the buffer.
-    pub fn position(&self) -> DoubleProperty {
-        DoubleProperty {
-            index: libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_POSITION,
-            node_handle: self.handle,
-        }
+    pub fn position(&self) -> DoubleProperty<'_, B> {
+        DoubleProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_POSITION,
+        )
     }
 
     /// Returns the rate property.
@@ -89,10 +121,10 @@ impl BufferNode {
     /// Default value: 1.0
     ///
     /// A multiplier that applies to playback rate. 1.0 is identity. Values less than 1.0 cause a decrease in pitch and values greater than 1.0 cause an increase in pitch.
-    pub fn rate(&self) -> DoubleProperty {
-        DoubleProperty {
-            index: libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_RATE,
-            node_handle: self.handle,
-        }
+    pub fn rate(&self) -> DoubleProperty<'_, B> {
+        DoubleProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_BUFFER_PROPERTIES_Lav_BUFFER_RATE,
+        )
     }
 }
@@ -3,9 +3,11 @@ use super::properties::{
     DistanceModelProperty, Float3Property, Float6Property, FloatProperty, IntProperty,
     PanningStrategyProperty,
 };
-use super::Node;
+use super::{Node, NodeType, TypedNode};
 use check;
+use lav_ptr::LavPtr;
 use std::ffi::CString;
+use std::marker::PhantomData;
 use Result;
 
 /// This is the entry point to the 3D simulation capabilities. Environment nodes hold the information needed to pan sources, as well as acting as an aggregate output for all sources that use this environment.
@@ -18,31 +20,62 @@ use Result;
 /// index | channels | description
 /// ------|----------|------------
 /// 0 | Depends on the output_channels property. | The output of the 3D environment.
-pub struct EnvironmentNode {
+pub struct EnvironmentNode<'server, B = server::Unbranded> {
     // allow SourceNode to access this handle
-    pub(crate) handle: libaudioverse_sys::LavHandle,
+    pub(crate) handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
 }
 
-impl Node for EnvironmentNode {
+impl<'server, B> Clone for EnvironmentNode<'server, B> {
+    fn clone(&self) -> EnvironmentNode<'server, B> {
+        EnvironmentNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for EnvironmentNode<'server, B> {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for EnvironmentNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::EnvironmentNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> EnvironmentNode<'server, B> {
+        EnvironmentNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
         self.handle
     }
 }
 
-impl EnvironmentNode {
+impl<'server, B> EnvironmentNode<'server, B> {
     /// Creates a new environment node.
-    pub fn new(server: &server::Server, hrtf_path: &CString) -> Result<EnvironmentNode> {
+    pub fn new<M>(
+        server: &server::Server<'server, M, B>,
+        hrtf_path: &CString,
+    ) -> Result<EnvironmentNode<'server, B>> {
         let mut node_handle: libaudioverse_sys::LavHandle = 0;
         check(unsafe {
             libaudioverse_sys::Lav_createEnvironmentNode(
-                server.handle,
+                *server.handle,
                 hrtf_path.as_ptr(),
                 &mut node_handle,
             )
         })?;
         Ok(EnvironmentNode {
-            handle: node_handle,
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
         })
     }
 
@@ -54,8 +87,11 @@ impl EnvironmentNode {
     ///
     /// The distance at which a source will be heard only in the reverb.
     /// See documentation on the SourceNode node for a specific explanation. By default, sources get the value of this property from the environment. To control this property on a per-source basis, set Lav_SOURCE_CONTROL_REVERB to true on the source.
-    pub fn default_reverb_distance(&self) -> FloatProperty {
-        FloatProperty { index : libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_REVERB_DISTANCE,  node_handle : self.handle }
+    pub fn default_reverb_distance(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_REVERB_DISTANCE,
+        )
     }
 
     /// Returns the default_size property.
@@ -65,12 +101,11 @@ impl EnvironmentNode {
     /// Default value: 0.0
     ///
     /// The default size for new sources. Sources aare approximated as spheres, with 0 being the special case of a point source. Size is used to determine the listener’s distance from a source.
-    pub fn default_size(&self) -> FloatProperty {
-        FloatProperty {
-            index:
-                libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_DEFAULT_SIZE,
-            node_handle: self.handle,
-        }
+    pub fn default_size(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_DEFAULT_SIZE,
+        )
     }
 
     /// Returns the distance_model property.
@@ -81,8 +116,11 @@ impl EnvironmentNode {
     ///
     /// Distance models control how quickly sources get quieter as they move away from the listener.
     /// By default, sources are configured to delegate to the environment when looking for values to use for the distance model parameters. This behavior may be changed by setting Lav_SOURCE_CONTROL_DISTANCE_MODEL to true.
-    pub fn distance_model(&self) -> DistanceModelProperty {
-        DistanceModelProperty { index : libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_DISTANCE_MODEL,  node_handle : self.handle }
+    pub fn distance_model(&self) -> DistanceModelProperty<'_> {
+        DistanceModelProperty::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_DISTANCE_MODEL,
+        )
     }
 
     /// Returns the max_distance property.
@@ -93,12 +131,11 @@ impl EnvironmentNode {
     ///
     /// The maximum distance at which a source is audible. Consider this property to be in meters.
     /// By default, sources are configured to delegate to the environment when looking for values to use for the distance model parameters. This behavior may be changed by setting Lav_SOURCE_CONTROL_DISTANCE_MODEL to true.
-    pub fn max_distance(&self) -> FloatProperty {
-        FloatProperty {
-            index:
-                libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_MAX_DISTANCE,
-            node_handle: self.handle,
-        }
+    pub fn max_distance(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_MAX_DISTANCE,
+        )
     }
 
     /// Returns the max_reverb_level property.
@@ -110,8 +147,11 @@ impl EnvironmentNode {
     /// The maximum amount of audio to be diverted to reverb sends, if any.
     /// Behavior is undefined if this property is ever less than Lav_ENVIRONMENT_MIN_REVERB_LEVEL.
     /// By default, sources look to their environmlent for the value of this property. If you wish to set it on a per-source basis, set Lav_SOURCE_CONTROL_REVERB to true on the source.
-    pub fn max_reverb_level(&self) -> FloatProperty {
-        FloatProperty { index : libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_MAX_REVERB_LEVEL,  node_handle : self.handle }
+    pub fn max_reverb_level(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_MAX_REVERB_LEVEL,
+        )
     }
 
     /// Returns the min_reverb_level property.
@@ -124,8 +164,11 @@ impl EnvironmentNode {
     /// if a send is configured to be a reverb send, this is the minimum amount of audio that will be diverted to it.
     /// Behavior is undefined if this property is ever greater than the value of Lav_ENVIRONMENT_MAX_REVERB_LEVEL.
     /// By default, sources look to their environment for the value of this property. If you wish to set it on a per-source basis, set Lav_SOURCE_CONTROL_REVERB to true on the source.
-    pub fn min_reverb_level(&self) -> FloatProperty {
-        FloatProperty { index : libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_MIN_REVERB_LEVEL,  node_handle : self.handle }
+    pub fn min_reverb_level(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_MIN_REVERB_LEVEL,
+        )
     }
 
     /// Returns the orientation property.
@@ -137,12 +180,11 @@ impl EnvironmentNode {
     /// This property packs these vectors because they must never be modified separately. Additionally, they should both be unit vectors and must also be orthoganal.
     ///
     /// the default situates the listener such that positive x is right, positive y is up, and positive z is behind the listener. The setting (0, 1, 0, 0, 0, 1) will situate the listener such that positive x is right and positive y is forward. For those not familiar with trigonometry and who wish to consider positive x east and positivve y north, the following formula will turn the listener to face a scertain direction specified in radians clockwise of north: (sin(theta), cos(theta), 0, 0, 0, 1). As usual, note that radians=degrees*PI/180.
-    pub fn orientation(&self) -> Float6Property {
-        Float6Property {
-            index:
-                libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_ORIENTATION,
-            node_handle: self.handle,
-        }
+    pub fn orientation(&self) -> Float6Property<'_> {
+        Float6Property::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_ORIENTATION,
+        )
     }
 
     /// Returns the output_channels property.
@@ -154,8 +196,11 @@ impl EnvironmentNode {
     /// Environments are not smart enough to determine the number of channels their output needs to have.
     /// If you are using something greater than stereo, i.e. 5.1, you need to change this property. The specific issue solved by this property is the case in which one source is set to something different than all others, or where the app changes the panning strategies of sources after creation.
     /// Values besides 2, 4, 6, or 8 do not usually have much meaning.
-    pub fn output_channels(&self) -> IntProperty {
-        IntProperty { index : libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_OUTPUT_CHANNELS,  node_handle : self.handle }
+    pub fn output_channels(&self) -> IntProperty<'_> {
+        IntProperty::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_OUTPUT_CHANNELS,
+        )
     }
 
     /// Returns the panning_strategy property.
@@ -166,8 +211,11 @@ impl EnvironmentNode {
     ///
     /// The panning strategy for any source configured to delegate to the environment. All new sources delegate to the environment by default.
     /// If you want to change this property for a specific source, set Lav_SOURCE_CONTROL_PANNING on the source to true.
-    pub fn panning_strategy(&self) -> PanningStrategyProperty {
-        PanningStrategyProperty { index : libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_PANNING_STRATEGY,  node_handle : self.handle }
+    pub fn panning_strategy(&self) -> PanningStrategyProperty<'_> {
+        PanningStrategyProperty::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_PANNING_STRATEGY,
+        )
     }
 
     /// Returns the position property.
@@ -175,18 +223,34 @@ impl EnvironmentNode {
     /// Default value: [0.0, 0.0, 0.0]
     ///
     /// The position of the listener, in world coordinates.
-    pub fn position(&self) -> Float3Property {
-        Float3Property {
-            index: libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_POSITION,
-            node_handle: self.handle,
-        }
+    pub fn position(&self) -> Float3Property<'_> {
+        Float3Property::new(
+            *self.handle,
+            libaudioverse_sys::lav_STANDARD_ENVIRONMENT_PROPERTIES_Lav_ENVIRONMENT_POSITION,
+        )
     }
 
     ///  Play a buffer, using the specified position and the currently set defaults on the world for distance model and panning strategy. This is the same as creating a buffer and a source, but Libaudioverse retains control of these objects. When the buffer finishes playing, the source is automatically disposed of.
     /// If is_dry is  true, we avoid sending to the effect sends configured as defaults.
+    ///
+    /// The buffer must come from the same server as the environment, which is checked at compile time for branded servers:
+    ///
+    /// ```compile_fail
+    /// # use libaudioverse::{Buffer, Libaudioverse, Server};
+    /// # use libaudioverse::nodes::EnvironmentNode;
+    /// # use std::ffi::CString;
+    /// let lib = Libaudioverse::new().unwrap();
+    /// Server::new(&lib).unwrap().branded(|first| {
+    ///     Server::new(&lib).unwrap().branded(|second| {
+    ///         let environment = EnvironmentNode::new(&first, &CString::new("default").unwrap()).unwrap();
+    ///         let buffer = Buffer::new(&second).unwrap();
+    ///         environment.play_async(&buffer, 0.0, 0.0, 0.0, false).unwrap();
+    ///     })
+    /// });
+    /// ```
     pub fn play_async(
         &self,
-        buffer: &buffer::Buffer,
+        buffer: &buffer::Buffer<'_, B>,
         x: f32,
         y: f32,
         z: f32,
@@ -194,8 +258,8 @@ impl EnvironmentNode {
     ) -> Result<()> {
         check(unsafe {
             libaudioverse_sys::Lav_environmentNodePlayAsync(
-                self.handle,
-                buffer.handle,
+                *self.handle,
+                *buffer.handle,
                 x,
                 y,
                 z,
@@ -1,8 +1,10 @@
 use super::super::{libaudioverse_sys, server};
 use super::properties::{BoolProperty, DoubleProperty};
-use super::Node;
+use super::{Node, NodeType, TypedNode};
 use check;
+use lav_ptr::LavPtr;
 use std::ffi::CString;
+use std::marker::PhantomData;
 use Result;
 
 /// Streams a file, which must be specified to the constructor and cannot be changed thereafter.
@@ -16,40 +18,71 @@ use Result;
 /// index | channels | description
 /// ------|----------|------------
 /// 0 | Depends on the file. | The output of the stream.
-pub struct FileStreamerNode {
-    handle: libaudioverse_sys::LavHandle,
+pub struct FileStreamerNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
 }
 
-impl Node for FileStreamerNode {
+impl<'server, B> Clone for FileStreamerNode<'server, B> {
+    fn clone(&self) -> FileStreamerNode<'server, B> {
+        FileStreamerNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for FileStreamerNode<'server, B> {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for FileStreamerNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::FileStreamerNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> FileStreamerNode<'server, B> {
+        FileStreamerNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
         self.handle
     }
 }
 
-impl FileStreamerNode {
+impl<'server, B> FileStreamerNode<'server, B> {
     /// Creates a new file streamer node.
-    pub fn new(server: &server::Server, path: &CString) -> Result<FileStreamerNode> {
+    pub fn new<M>(
+        server: &server::Server<'server, M, B>,
+        path: &CString,
+    ) -> Result<FileStreamerNode<'server, B>> {
         let mut node_handle: libaudioverse_sys::LavHandle = 0;
         check(unsafe {
             libaudioverse_sys::Lav_createFileStreamerNode(
-                server.handle,
+                *server.handle,
                 path.as_ptr(),
                 &mut node_handle,
             )
         })?;
         Ok(FileStreamerNode {
-            handle: node_handle,
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
         })
     }
 
     /// Returns the ended property.
     /// This property is read-only. Switches from false to true once the stream has ended completely and gone silent. This property will never go true unless looping is false.
-    pub fn ended(&self) -> BoolProperty {
-        BoolProperty {
-            index: libaudioverse_sys::Lav_FILE_STREAMER_PROPERTIES_Lav_FILE_STREAMER_ENDED,
-            node_handle: self.handle,
-        }
+    pub fn ended(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_FILE_STREAMER_PROPERTIES_Lav_FILE_STREAMER_ENDED,
+        )
     }
 
     // Returns the looping property.
@@ -57,11 +90,11 @@ impl FileStreamerNode {
     /// Default value: False
     ///
     /// If true, this node repeats the file from the beginning once it reaches the end. Note that setting looping means that ended will never go true. If ended is already true, it may take until the end of the next processing block for ended to properly go false once more.
-    pub fn looping(&self) -> BoolProperty {
-        BoolProperty {
-            index: libaudioverse_sys::Lav_FILE_STREAMER_PROPERTIES_Lav_FILE_STREAMER_LOOPING,
-            node_handle: self.handle,
-        }
+    pub fn looping(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_FILE_STREAMER_PROPERTIES_Lav_FILE_STREAMER_LOOPING,
+        )
     }
 
     // Returns the position property.
@@ -71,10 +104,10 @@ impl FileStreamerNode {
     /// Default value: 0.0
     ///
     /// The position of playback, in seconds. The range of this property corresponds to the total duration of the file. Note that this property may be slightly inaccurate because this node has to pass data through a resampler.
-    pub fn position(&self) -> DoubleProperty {
-        DoubleProperty {
-            index: libaudioverse_sys::Lav_FILE_STREAMER_PROPERTIES_Lav_FILE_STREAMER_POSITION,
-            node_handle: self.handle,
-        }
+    pub fn position(&self) -> DoubleProperty<'_, B> {
+        DoubleProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_FILE_STREAMER_PROPERTIES_Lav_FILE_STREAMER_POSITION,
+        )
     }
 }
@@ -1,6 +1,8 @@
 use super::super::{libaudioverse_sys, server};
-use super::Node;
+use super::{Node, NodeType, TypedNode};
 use check;
+use lav_ptr::LavPtr;
+use std::marker::PhantomData;
 use Result;
 
 /// This node is essentially in instantiated generic node, offering only the functionality therein. Its purpose is to allow changing the gain or adding offset to a large collection of nodes. One possible use is as a simple mixer: point all the nodes to be mixed at the input, set mul, and then point the output at the destination for the mixed audio.
@@ -17,26 +19,57 @@ use Result;
 /// ------|----------|------------
 /// 0 | Depends on arguments to this node’s constructor. | The signal with its gain changed.
 
-pub struct GainNode {
-    handle: libaudioverse_sys::LavHandle,
+pub struct GainNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
 }
 
-impl Node for GainNode {
+impl<'server, B> Clone for GainNode<'server, B> {
+    fn clone(&self) -> GainNode<'server, B> {
+        GainNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for GainNode<'server, B> {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for GainNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::GainNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> GainNode<'server, B> {
+        GainNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
         self.handle
     }
 }
 
-impl GainNode {
+impl<'server, B> GainNode<'server, B> {
     /// Creates a new gain node.
-    pub fn new(server: &server::Server, channels: i32) -> Result<GainNode> {
+    pub fn new<M>(
+        server: &server::Server<'server, M, B>,
+        channels: i32,
+    ) -> Result<GainNode<'server, B>> {
         let mut node_handle: libaudioverse_sys::LavHandle = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_createGainNode(server.handle, channels, &mut node_handle)
+            libaudioverse_sys::Lav_createGainNode(*server.handle, channels, &mut node_handle)
         })?;
         Ok(GainNode {
-            handle: node_handle,
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
         })
     }
 }
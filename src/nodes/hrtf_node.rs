@@ -1,8 +1,10 @@
 use super::super::{libaudioverse_sys, server};
 use super::properties::{BoolProperty, FloatProperty};
-use super::Node;
+use super::{Node, NodeType, TypedNode};
 use check;
+use lav_ptr::LavPtr;
 use std::ffi::CString;
+use std::marker::PhantomData;
 use Result;
 
 /// This node implements an HRTF panner.
@@ -18,31 +20,62 @@ use Result;
 /// index | channels | description
 /// ------|----------|------------
 /// 0 | 2 | The signal with the HRTF applied.
-pub struct HrtfNode {
-    handle: libaudioverse_sys::LavHandle,
+pub struct HrtfNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
 }
 
-impl Node for HrtfNode {
+impl<'server, B> Clone for HrtfNode<'server, B> {
+    fn clone(&self) -> HrtfNode<'server, B> {
+        HrtfNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for HrtfNode<'server, B> {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for HrtfNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::HrtfNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> HrtfNode<'server, B> {
+        HrtfNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
         self.handle
     }
 }
 
-impl HrtfNode {
+impl<'server, B> HrtfNode<'server, B> {
     /// Creates a new HRTF node.
     ///  You can use either Libaudioverse’s internal HRTF (The Diffuse MIT Kemar Dataset) by passing “default” as the HRTf file name, or an HRTF of your own.
-    pub fn new(server: &server::Server, hrtf_path: &CString) -> Result<HrtfNode> {
+    pub fn new<M>(
+        server: &server::Server<'server, M, B>,
+        hrtf_path: &CString,
+    ) -> Result<HrtfNode<'server, B>> {
         let mut node_handle: libaudioverse_sys::LavHandle = 0;
         check(unsafe {
             libaudioverse_sys::Lav_createHrtfNode(
-                server.handle,
+                *server.handle,
                 hrtf_path.as_ptr(),
                 &mut node_handle,
             )
         })?;
         Ok(HrtfNode {
-            handle: node_handle,
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
         })
     }
 
@@ -53,11 +86,11 @@ impl HrtfNode {
     /// Default value: 0.0
     ///
     /// The horizontal angle of the panner in degrees. 0 is straight ahead and positive values are clockwise.
-    pub fn azimuth(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_AZIMUTH,
-            node_handle: self.handle,
-        }
+    pub fn azimuth(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_AZIMUTH,
+        )
     }
 
     /// Returns the elevation property.
@@ -67,11 +100,11 @@ impl HrtfNode {
     /// Default value: 0.0
     ///
     /// The vertical angle of the panner in degrees. 0 is horizontal and positive values move upward.
-    pub fn elevation(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_ELEVATION,
-            node_handle: self.handle,
-        }
+    pub fn elevation(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_ELEVATION,
+        )
     }
 
     /// Returns the should_crossfade property.
@@ -79,10 +112,10 @@ impl HrtfNode {
     /// Default value: True
     ///
     /// By default, panners crossfade their output. This property allows such functionality to be disabled. Note that for HRTF nodes, crossfading is more important than for other panner types. Unlike other panner types, the audio artifacts produced by disabling crossfading are noticeable, even for updates of only a few degrees.
-    pub fn should_crossfade(&self) -> BoolProperty {
-        BoolProperty {
-            index: libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_SHOULD_CROSSFADE,
-            node_handle: self.handle,
-        }
+    pub fn should_crossfade(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_SHOULD_CROSSFADE,
+        )
     }
 }
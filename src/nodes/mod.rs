@@ -2,35 +2,51 @@
 //! Nodes represent audio transformation, genneration, and analysis.
 //!
 
+mod amplitude_panner_node;
+mod any_node;
 mod buffer_node;
 mod environment_node;
 mod file_streamer_node;
 mod gain_node;
 mod hrtf_node;
+mod node_type;
 pub mod properties;
 // pub mod multipanner_node;
 mod pull_node;
 mod push_node;
 mod source_node;
 
-use self::properties::{FloatProperty, NodeStateProperty};
+use self::properties::{
+    Automatable, DynamicProperty, FloatProperty, NodeStateProperty, Properties,
+};
 use super::libaudioverse_sys;
 use super::*;
 use check;
-use std::os::raw::c_uint;
+use lav_ptr::LavPtr;
+use std::marker::PhantomData;
+use std::os::raw::{c_int, c_uint};
 
 #[doc(inline)]
 pub use self::{
-    buffer_node::BufferNode, environment_node::EnvironmentNode,
-    file_streamer_node::FileStreamerNode, gain_node::GainNode, hrtf_node::HrtfNode,
-    pull_node::PullNode, push_node::PushNode, source_node::SourceNode,
+    amplitude_panner_node::AmplitudePannerNode,
+    any_node::{AnyNode, TypedNode},
+    buffer_node::BufferNode,
+    environment_node::EnvironmentNode,
+    file_streamer_node::FileStreamerNode,
+    gain_node::GainNode,
+    hrtf_node::HrtfNode,
+    node_type::NodeType,
+    pull_node::PullNode,
+    push_node::PushNode,
+    source_node::SourceNode,
 };
 
 /// Functionality and properties available on every Libaudioverse node.
-pub trait Node {
+/// `B` is the brand of the server the node was created on (see `Server::branded`), so nodes can only be connected to nodes and properties of the same brand.
+pub trait Node<B = server::Unbranded> {
     /// Connect the specified output of the specified node to the specified input of the specified node.
     /// It is an error if this would cause a cycle in the graph of nodes.
-    fn connect(&self, output: i32, destination: &Node, input: i32) -> Result<()> {
+    fn connect(&self, output: i32, destination: &Node<B>, input: i32) -> Result<()> {
         check(unsafe {
             libaudioverse_sys::Lav_nodeConnect(
                 self.get_handle(),
@@ -42,19 +58,21 @@ pub trait Node {
         Ok(())
     }
 
-    /// Connect a node’s output to an automatable property.
-    fn connect_property(&self, output: i32, destination: &Node, slot: i32) -> Result<()> {
+    /// Connect a node’s output to an automatable property, such as the `mul` property of another node.
+    /// Libaudioverse cannot remove a single property connection, so the only way to undo this is `disconnect_all`, which also removes the output's other connections.
+    fn connect_property(&self, output: i32, destination: &Automatable<B>) -> Result<()> {
+        let (node_handle, slot) = destination.slot();
         check(unsafe {
-            libaudioverse_sys::Lav_nodeConnectProperty(
-                self.get_handle(),
-                output,
-                destination.get_handle(),
-                slot,
-            )
+            libaudioverse_sys::Lav_nodeConnectProperty(self.get_handle(), output, node_handle, slot)
         })?;
         Ok(())
     }
 
+    /// Disconnect the specified output from everything it is connected to, including properties and the server.
+    fn disconnect_all(&self, output: i32) -> Result<()> {
+        check(unsafe { libaudioverse_sys::Lav_nodeDisconnect(self.get_handle(), output, 0, 0) })
+    }
+
     /// Connect the specified output of the specified node to the server’s input.
     /// Any node which is connected directly or indirectly to the server will remain alive even if your program lets go of it. For more details on the subject of node lifetimes, see the Libaudioverse manual.
     fn connect_server(&self, output: i32) -> Result<()> {
@@ -63,7 +81,7 @@ pub trait Node {
     }
 
     /// Disconnect the output of the specified node.
-    fn disconnect(&self, output: i32, destination: &Node, input: i32) -> Result<()> {
+    fn disconnect(&self, output: i32, destination: &Node<B>, input: i32) -> Result<()> {
         check(unsafe {
             libaudioverse_sys::Lav_nodeDisconnect(
                 self.get_handle(),
@@ -79,6 +97,30 @@ pub trait Node {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle;
 
+    /// Returns the server this node was created on.
+    fn get_server(&self) -> Result<Server<'_, AnyMode, B>> {
+        let mut server_handle: libaudioverse_sys::LavHandle = 0;
+        check(unsafe {
+            libaudioverse_sys::Lav_nodeGetServer(self.get_handle(), &mut server_handle)
+        })?;
+        Ok(Server {
+            handle: LavPtr::new(server_handle)?,
+            lib: PhantomData,
+            mode: PhantomData,
+            brand: PhantomData,
+        })
+    }
+
+    /// Returns the type of this node.
+    fn node_type(&self) -> Result<NodeType> {
+        let mut object_type: c_int = 0;
+        check(unsafe {
+            libaudioverse_sys::Lav_handleGetType(self.get_handle(), &mut object_type)
+        })?;
+        NodeType::from_raw(object_type)
+            .ok_or_else(|| Error::new(ErrorKind::Unknown, "Unknown node type"))
+    }
+
     /// Get the number of inputs this node has.
     fn get_input_connection_count(&self) -> Result<u32> {
         let mut inputs: c_uint = 0;
@@ -97,6 +139,17 @@ pub trait Node {
         Ok(outputs)
     }
 
+    /// Returns an iterator over the properties of this node, describing each of them.
+    /// Libaudioverse cannot list the properties of a node directly, so this probes every index properties may have; collect the results rather than calling this repeatedly.
+    fn properties(&self) -> Properties<'_> {
+        Properties::new(self.get_handle())
+    }
+
+    /// Returns the property with the specified name, as listed in the Libaudioverse manual (for example "mul").
+    fn property(&self, name: &str) -> Result<DynamicProperty<'_, B>> {
+        DynamicProperty::find(self.get_handle(), name)
+    }
+
     /// Equivalent to disconnecting all of the outputs of this node. After a call to isolate, this node will no longer be affecting audio in any way.
     fn isolate(&self) -> Result<()> {
         check(unsafe { libaudioverse_sys::Lav_nodeIsolate(self.get_handle()) })
@@ -114,11 +167,11 @@ pub trait Node {
     /// Default value: 0.0
     ///
     /// After mul is applied, we add the value to which this property is set to the node’s result.
-    fn add(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::lav_STANDARD_PROPERTIES_Lav_NODE_ADD,
-            node_handle: self.get_handle(),
-        }
+    fn add(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            self.get_handle(),
+            libaudioverse_sys::lav_STANDARD_PROPERTIES_Lav_NODE_ADD,
+        )
     }
 
     /// Returns the mul property.
@@ -128,11 +181,11 @@ pub trait Node {
     /// Default value: 1.0
     ///
     /// After this node processes, the value to which mul is set is used as a multiplier on the result. The most notable effect of this is to change the node’s volume. A variety of other uses exist, however, especially as regards to nodes which are connected to properties. Mul is applied before add.
-    fn mul(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::lav_STANDARD_PROPERTIES_Lav_NODE_MUL,
-            node_handle: self.get_handle(),
-        }
+    fn mul(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            self.get_handle(),
+            libaudioverse_sys::lav_STANDARD_PROPERTIES_Lav_NODE_MUL,
+        )
     }
 
     /// Returns the state property.
@@ -142,10 +195,10 @@ pub trait Node {
     /// Default value: NodeState::Playing
     ///
     /// The node’s state. See the basics section in the Libaudioverse manual for details. The default is usually what you want.
-    fn state(&self) -> NodeStateProperty {
-        NodeStateProperty {
-            index: libaudioverse_sys::lav_STANDARD_PROPERTIES_Lav_NODE_STATE,
-            node_handle: self.get_handle(),
-        }
+    fn state(&self) -> NodeStateProperty<'_> {
+        NodeStateProperty::new(
+            self.get_handle(),
+            libaudioverse_sys::lav_STANDARD_PROPERTIES_Lav_NODE_STATE,
+        )
     }
 }
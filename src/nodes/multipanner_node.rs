@@ -3,6 +3,7 @@ use super::super::{libaudioverse_sys, server};
 use super::Node;
 use super::properties::{ bool_property, float_property, panning_strategy_property };
 use check;
+use lav_ptr::LavPtr;
 use Result;
 
 /// A panner which can have the algorithm it uses changed at runtime. The use for multipanners is for applications in which we may wish to change the speaker configuration at runtime. Capabilities include switching from HRTF to stereo and back, a useful property for games wherein the user might or might not be using headphones.
@@ -19,13 +20,13 @@ use Result;
 /// ------|----------|------------
 /// 0 | Depends on the currently set panning strategy. | The signal, panned according to the configured panning strategy.
 pub struct MultipannerNode {
-    handle: libaudioverse_sys::LavHandle
+    handle: LavPtr,
 }
 
 impl Node for MultipannerNode {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
-        self.handle
+        *self.handle
     }
 }
 
@@ -46,8 +47,8 @@ impl MultipannerNode {
     /// Default value: 0.0
     /// 
     /// The horizontal angle of the panner in degrees. 0 is straight ahead and positive values are clockwise.
-    pub fn azimuth(&self) -> float_property::FloatProperty {
-        float_property::FloatProperty { index : libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_AZIMUTH,  node_handle : self.handle }
+    pub fn azimuth(&self) -> float_property::FloatProperty<'_> {
+        float_property::FloatProperty::new(*self.handle, libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_AZIMUTH)
     }
     
     /// Returns the elevation property.
@@ -57,8 +58,8 @@ impl MultipannerNode {
     /// Default value: 0.0
     /// 
     /// The vertical angle of the panner in degrees. 0 is horizontal and positive values move upward.
-    pub fn elevation(&self) -> float_property::FloatProperty {
-        float_property::FloatProperty { index : libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_ELEVATION,  node_handle : self.handle }
+    pub fn elevation(&self) -> float_property::FloatProperty<'_> {
+        float_property::FloatProperty::new(*self.handle, libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_ELEVATION)
     }
     
     /// Returns the should_crossfade property.
@@ -66,8 +67,8 @@ impl MultipannerNode {
     /// Default value: True
     /// 
     /// Whether or not this panner should crossfade. Lack of crossfading introduces audible artifacts when the panner is moved. You usually want this on.
-    pub fn should_crossfade(&self) -> bool_property::BoolProperty {
-        bool_property::BoolProperty { index : libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_SHOULD_CROSSFADE,  node_handle : self.handle }
+    pub fn should_crossfade(&self) -> bool_property::BoolProperty<'_> {
+        bool_property::BoolProperty::new(*self.handle, libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_SHOULD_CROSSFADE)
     }
     
     /// Returns the strategy property. 
@@ -77,7 +78,7 @@ impl MultipannerNode {
     /// Default value: PanningStrategy::Stereo
     /// 
     /// What type of panning to use. Possibilities include HRTF, stereo, 5.1, and 7.1 speaker configurations. For something more nontraditional, use an amplitude panner.
-    pub fn strategy(&self) -> panning_strategy_property::PanningStrategyProperty {
-        panning_strategy_property::PanningStrategyProperty { index : libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_STRATEGY,  node_handle : self.handle }
+    pub fn strategy(&self) -> panning_strategy_property::PanningStrategyProperty<'_> {
+        panning_strategy_property::PanningStrategyProperty::new(*self.handle, libaudioverse_sys::Lav_PANNER_PROPERTIES_Lav_PANNER_STRATEGY)
     }
 }
\ No newline at end of file
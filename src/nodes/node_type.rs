@@ -0,0 +1,199 @@
+//! The types of objects which Libaudioverse can create.
+use super::super::libaudioverse_sys;
+use std::os::raw::c_int;
+
+/// The type of a Libaudioverse object, as reported by `Node::node_type`.
+/// Besides every kind of node, this includes servers and buffers, since Libaudioverse identifies all of its objects in the same way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeType {
+    /// A server.
+    Server,
+    /// A buffer.
+    Buffer,
+    /// A generic node.
+    GenericNode,
+    /// An environment node.
+    EnvironmentNode,
+    /// A source node.
+    SourceNode,
+    /// An HRTF node.
+    HrtfNode,
+    /// A sine node.
+    SineNode,
+    /// A hard limiter node.
+    HardLimiterNode,
+    /// A crossfading delay node.
+    CrossfadingDelayNode,
+    /// A dopplering delay node.
+    DoppleringDelayNode,
+    /// An amplitude panner node.
+    AmplitudePannerNode,
+    /// A push node.
+    PushNode,
+    /// A biquad node.
+    BiquadNode,
+    /// A pull node.
+    PullNode,
+    /// A graph listener node.
+    GraphListenerNode,
+    /// A ring modulator node.
+    RingmodNode,
+    /// A multipanner node.
+    MultipannerNode,
+    /// A feedback delay network node.
+    FeedbackDelayNetworkNode,
+    /// An additive square node.
+    AdditiveSquareNode,
+    /// An additive triangle node.
+    AdditiveTriangleNode,
+    /// An additive saw node.
+    AdditiveSawNode,
+    /// A noise node.
+    NoiseNode,
+    /// An IIR node.
+    IirNode,
+    /// A gain node.
+    GainNode,
+    /// A channel splitter node.
+    ChannelSplitterNode,
+    /// A channel merger node.
+    ChannelMergerNode,
+    /// A buffer node.
+    BufferNode,
+    /// A buffer timeline node.
+    BufferTimelineNode,
+    /// A recorder node.
+    RecorderNode,
+    /// A convolver node.
+    ConvolverNode,
+    /// An FFT convolver node.
+    FftConvolverNode,
+    /// A three band eq node.
+    ThreeBandEqNode,
+    /// A filtered delay node.
+    FilteredDelayNode,
+    /// A crossfader node.
+    CrossfaderNode,
+    /// An one pole filter node.
+    OnePoleFilterNode,
+    /// A first order filter node.
+    FirstOrderFilterNode,
+    /// An allpass node.
+    AllpassNode,
+    /// An FDN reverb node.
+    FdnReverbNode,
+    /// A BLIT node.
+    BlitNode,
+    /// A DC blocker node.
+    DcBlockerNode,
+    /// A leaky integrator node.
+    LeakyIntegratorNode,
+    /// A file streamer node.
+    FileStreamerNode,
+}
+
+impl NodeType {
+    /// Returns the type corresponding to a raw Libaudioverse object type, or `None` if it is not known to these bindings.
+    pub fn from_raw(object_type: c_int) -> Option<NodeType> {
+        Some(match object_type {
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_SERVER => NodeType::Server,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_BUFFER => NodeType::Buffer,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_GENERIC_NODE => NodeType::GenericNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_ENVIRONMENT_NODE => {
+                NodeType::EnvironmentNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_SOURCE_NODE => NodeType::SourceNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_HRTF_NODE => NodeType::HrtfNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_SINE_NODE => NodeType::SineNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_HARD_LIMITER_NODE => {
+                NodeType::HardLimiterNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_CROSSFADING_DELAY_NODE => {
+                NodeType::CrossfadingDelayNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_DOPPLERING_DELAY_NODE => {
+                NodeType::DoppleringDelayNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_AMPLITUDE_PANNER_NODE => {
+                NodeType::AmplitudePannerNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_PUSH_NODE => NodeType::PushNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_BIQUAD_NODE => NodeType::BiquadNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_PULL_NODE => NodeType::PullNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_GRAPH_LISTENER_NODE => {
+                NodeType::GraphListenerNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_RINGMOD_NODE => NodeType::RingmodNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_MULTIPANNER_NODE => {
+                NodeType::MultipannerNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_FEEDBACK_DELAY_NETWORK_NODE => {
+                NodeType::FeedbackDelayNetworkNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_ADDITIVE_SQUARE_NODE => {
+                NodeType::AdditiveSquareNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_ADDITIVE_TRIANGLE_NODE => {
+                NodeType::AdditiveTriangleNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_ADDITIVE_SAW_NODE => {
+                NodeType::AdditiveSawNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_NOISE_NODE => NodeType::NoiseNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_IIR_NODE => NodeType::IirNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_GAIN_NODE => NodeType::GainNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_CHANNEL_SPLITTER_NODE => {
+                NodeType::ChannelSplitterNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_CHANNEL_MERGER_NODE => {
+                NodeType::ChannelMergerNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_BUFFER_NODE => NodeType::BufferNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_BUFFER_TIMELINE_NODE => {
+                NodeType::BufferTimelineNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_RECORDER_NODE => NodeType::RecorderNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_CONVOLVER_NODE => {
+                NodeType::ConvolverNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_FFT_CONVOLVER_NODE => {
+                NodeType::FftConvolverNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_THREE_BAND_EQ_NODE => {
+                NodeType::ThreeBandEqNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_FILTERED_DELAY_NODE => {
+                NodeType::FilteredDelayNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_CROSSFADER_NODE => {
+                NodeType::CrossfaderNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_ONE_POLE_FILTER_NODE => {
+                NodeType::OnePoleFilterNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_FIRST_ORDER_FILTER_NODE => {
+                NodeType::FirstOrderFilterNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_ALLPASS_NODE => NodeType::AllpassNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_FDN_REVERB_NODE => {
+                NodeType::FdnReverbNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_BLIT_NODE => NodeType::BlitNode,
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_DC_BLOCKER_NODE => {
+                NodeType::DcBlockerNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_LEAKY_INTEGRATOR_NODE => {
+                NodeType::LeakyIntegratorNode
+            }
+            libaudioverse_sys::Lav_OBJECT_TYPES_Lav_OBJTYPE_FILE_STREAMER_NODE => {
+                NodeType::FileStreamerNode
+            }
+            _ => return None,
+        })
+    }
+
+    /// Returns whether objects of this type are nodes.
+    pub fn is_node(self) -> bool {
+        !matches!(self, NodeType::Server | NodeType::Buffer)
+    }
+}
@@ -0,0 +1,160 @@
+use super::super::super::Result;
+use {Error, ErrorKind};
+
+/// Returns the index one past the last element written by writing `count` elements at `start`.
+pub(crate) fn write_end(start: u32, count: usize) -> Result<u32> {
+    if count > u32::MAX as usize {
+        return Err(Error::new(
+            ErrorKind::Range,
+            "Too many values to write to an array property",
+        ));
+    }
+    start.checked_add(count as u32).ok_or_else(|| {
+        Error::new(
+            ErrorKind::Range,
+            "The written range extends past the largest index of an array property",
+        )
+    })
+}
+
+/// Defines the proxy for an array property whose elements are `$element`, using the Libaudioverse functions for that element type.
+/// Like the other proxies, the generated type carries the brand `B` of the node's server.
+macro_rules! array_property {
+    (
+        $(#[$attr:meta])*
+        $name:ident,
+        $element:ty,
+        $zero:expr,
+        $length:ty,
+        $replace:ident,
+        $read:ident,
+        $write:ident,
+        $get_length:ident
+    ) => {
+        $(#[$attr])*
+        pub struct $name<'node, B = Unbranded> {
+            pub(crate) raw: RawProperty<'node>,
+            brand: PhantomData<B>,
+        }
+
+        impl<'node, B> $name<'node, B> {
+            pub(crate) fn new(
+                node_handle: libaudioverse_sys::LavHandle,
+                index: c_int,
+            ) -> $name<'node, B> {
+                $name {
+                    raw: RawProperty::new(node_handle, index),
+                    brand: PhantomData,
+                }
+            }
+
+            /// Returns the contents of the array.
+            /// The server is locked while the elements are read, so the audio thread cannot change the array part of the way through.
+            pub fn get(&self) -> Result<Vec<$element>> {
+                let server = self.raw.server()?;
+                let _lock = server.lock()?;
+                (0..self.len()?).map(|i| self.read(i)).collect()
+            }
+
+            /// Replaces the contents of the array. The new length must be within the range returned by `length_range`.
+            pub fn replace(&self, values: &[$element]) -> Result<()> {
+                let length = array_property::write_end(0, values.len())?;
+                check(unsafe {
+                    libaudioverse_sys::$replace(
+                        self.raw.node_handle,
+                        self.raw.index,
+                        length,
+                        values.as_ptr() as *mut _,
+                    )
+                })
+            }
+
+            /// Returns the element at the specified index.
+            pub fn read(&self, index: u32) -> Result<$element> {
+                let mut value: $element = $zero;
+                check(unsafe {
+                    libaudioverse_sys::$read(
+                        self.raw.node_handle,
+                        self.raw.index,
+                        index,
+                        &mut value,
+                    )
+                })?;
+                Ok(value)
+            }
+
+            /// Overwrites part of the array, starting at `start`. The length of the array does not change, so the written range must lie within it.
+            pub fn write(&self, start: u32, values: &[$element]) -> Result<()> {
+                let end = array_property::write_end(start, values.len())?;
+                check(unsafe {
+                    libaudioverse_sys::$write(
+                        self.raw.node_handle,
+                        self.raw.index,
+                        start,
+                        end,
+                        values.as_ptr() as *mut _,
+                    )
+                })
+            }
+
+            /// Returns the length of the array.
+            pub fn len(&self) -> Result<u32> {
+                let mut length: $length = 0;
+                check(unsafe {
+                    libaudioverse_sys::$get_length(
+                        self.raw.node_handle,
+                        self.raw.index,
+                        &mut length,
+                    )
+                })?;
+                Ok(length as u32)
+            }
+
+            /// Returns whether the array is empty.
+            pub fn is_empty(&self) -> Result<bool> {
+                Ok(self.len()? == 0)
+            }
+
+            /// Returns the minimum and maximum lengths which the array may be given.
+            pub fn length_range(&self) -> Result<(u32, u32)> {
+                let mut min: c_uint = 0;
+                let mut max: c_uint = 0;
+                check(unsafe {
+                    libaudioverse_sys::Lav_nodeGetArrayPropertyLengthRange(
+                        self.raw.node_handle,
+                        self.raw.index,
+                        &mut min,
+                        &mut max,
+                    )
+                })?;
+                Ok((min, max))
+            }
+        }
+
+        impl<'node, B> Property<Vec<$element>> for $name<'node, B> {
+            fn get(&self) -> Result<Vec<$element>> {
+                $name::get(self)
+            }
+
+            fn set(&self, value: Vec<$element>) -> Result<()> {
+                self.replace(&value)
+            }
+
+            #[doc(hidden)]
+            fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+                self.raw.node_handle
+            }
+
+            #[doc(hidden)]
+            fn index(&self) -> c_int {
+                self.raw.index
+            }
+        }
+    };
+}
+
+#[test]
+fn rejects_writes_past_the_largest_index() {
+    assert_eq!(write_end(2, 3).unwrap(), 5);
+    assert_eq!(write_end(u32::MAX, 1).unwrap_err().kind, ErrorKind::Range);
+}
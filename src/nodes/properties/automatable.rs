@@ -0,0 +1,81 @@
+use super::super::super::libaudioverse_sys;
+use super::super::super::Result;
+use super::{DoubleProperty, FloatProperty};
+use check;
+use server::Unbranded;
+use std::os::raw::c_int;
+
+/// Properties whose value can be scheduled ahead of time. Automation is applied by the audio thread, so changes happen on exact samples rather than whenever the calling thread gets to them.
+///
+/// Times are in seconds, relative to the current time of the server. Scheduling an automator which starts while another one is still running is an error of kind `ErrorKind::OverlappingAutomators`.
+/// `B` is the brand of the server the property's node belongs to.
+pub trait Automatable<B = Unbranded> {
+    /// Moves the property linearly from the end of the previous automator, or its current value, so that it reaches `value` at time `at`.
+    fn linear_ramp_to(&self, value: f64, at: f64) -> Result<()> {
+        let (node_handle, index) = self.slot();
+        check(unsafe {
+            libaudioverse_sys::Lav_automationLinearRampToValue(node_handle, index, at, value)
+        })
+    }
+
+    /// Sets the property to `value` at time `at`.
+    fn set_at(&self, value: f64, at: f64) -> Result<()> {
+        let (node_handle, index) = self.slot();
+        check(unsafe { libaudioverse_sys::Lav_automationSet(node_handle, index, at, value) })
+    }
+
+    /// Starting at time `at`, moves the property through `values`, which are spread evenly over `duration` seconds.
+    fn envelope(&self, at: f64, duration: f64, values: &[f64]) -> Result<()> {
+        let (node_handle, index) = self.slot();
+        check(unsafe {
+            libaudioverse_sys::Lav_automationEnvelope(
+                node_handle,
+                index,
+                at,
+                duration,
+                values.len() as c_int,
+                values.as_ptr() as *mut _,
+            )
+        })
+    }
+
+    /// Cancels every automator which starts after time `at`. The property keeps whatever value it had reached.
+    fn cancel_after(&self, at: f64) -> Result<()> {
+        let (node_handle, index) = self.slot();
+        check(unsafe { libaudioverse_sys::Lav_automationCancelAutomators(node_handle, index, at) })
+    }
+
+    /// Returns the handle of the node and the index of the property.
+    #[doc(hidden)]
+    fn slot(&self) -> (libaudioverse_sys::LavHandle, c_int);
+}
+
+impl<'node, B> Automatable<B> for FloatProperty<'node, B> {
+    #[doc(hidden)]
+    fn slot(&self) -> (libaudioverse_sys::LavHandle, c_int) {
+        (self.raw.node_handle, self.raw.index)
+    }
+}
+
+impl<'node, B> Automatable<B> for DoubleProperty<'node, B> {
+    #[doc(hidden)]
+    fn slot(&self) -> (libaudioverse_sys::LavHandle, c_int) {
+        (self.raw.node_handle, self.raw.index)
+    }
+}
+
+#[test]
+fn rejects_overlapping_automators() {
+    use nodes::{GainNode, Node};
+    use {ErrorKind, Libaudioverse, Server};
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    let node = GainNode::new(&server, 2).unwrap();
+    let mul = node.mul();
+    mul.envelope(0.0, 1.0, &[1.0, 0.5, 0.0]).unwrap();
+    let error = mul.set_at(1.0, 0.5).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::OverlappingAutomators);
+    mul.cancel_after(0.0).unwrap();
+    mul.linear_ramp_to(1.0, 0.5).unwrap();
+}
@@ -1,36 +1,48 @@
 use super::super::super::libaudioverse_sys;
 use super::super::super::Result;
 
+use super::property::{Property, RawProperty};
 use check;
 use std::os::raw::c_int;
-use Error;
+use {Error, ErrorKind};
 
 /// Proxy to a bool property.
-pub struct BoolProperty {
-    // allow nodes to construct instances of this struct
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+pub struct BoolProperty<'node> {
+    pub(crate) raw: RawProperty<'node>,
 }
 
-impl BoolProperty {
+impl<'node> BoolProperty<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> BoolProperty<'node> {
+        BoolProperty {
+            raw: RawProperty::new(node_handle, index),
+        }
+    }
+
     pub fn get(&self) -> Result<bool> {
         let mut value: i32 = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_nodeGetIntProperty(self.node_handle, self.index, &mut value)
+            libaudioverse_sys::Lav_nodeGetIntProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut value,
+            )
         })?;
         match value {
             0 => Ok(false),
             1 => Ok(true),
-            _ => Err(Error {
-                code: libaudioverse_sys::Lav_ERRORS_Lav_ERROR_UNKNOWN,
-                message: "Bool property out of range.".to_string(),
-            }),
+            _ => Err(Error::new(
+                ErrorKind::Unknown,
+                "Bool property out of range.",
+            )),
         }
     }
 
     fn set_int(&self, value: i32) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_nodeSetIntProperty(self.node_handle, self.index, value)
+            libaudioverse_sys::Lav_nodeSetIntProperty(self.raw.node_handle, self.raw.index, value)
         })?;
         Ok(())
     }
@@ -43,3 +55,23 @@ impl BoolProperty {
         }
     }
 }
+
+impl<'node> Property<bool> for BoolProperty<'node> {
+    fn get(&self) -> Result<bool> {
+        BoolProperty::get(self)
+    }
+
+    fn set(&self, value: bool) -> Result<()> {
+        BoolProperty::set(self, value)
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
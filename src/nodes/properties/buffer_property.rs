@@ -1,25 +1,99 @@
 use super::super::super::buffer;
 use super::super::super::libaudioverse_sys;
 use super::super::super::Result;
+use super::property::{Property, RawProperty};
 use check;
+use lav_ptr::LavPtr;
+use server::Unbranded;
+use std::marker::PhantomData;
 use std::os::raw::c_int;
 
-/// Proxy to a buffer property.
-pub struct BufferProperty {
-    // allow Server to construct instances of this struct
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+/// Proxy to a buffer property. `B` is the brand of the node's server, which must match the brand of buffers assigned to this property.
+pub struct BufferProperty<'node, B = Unbranded> {
+    pub(crate) raw: RawProperty<'node>,
+    brand: PhantomData<B>,
 }
 
-impl BufferProperty {
-    pub fn set(&self, buffer: &buffer::Buffer) -> Result<()> {
+impl<'node, B> BufferProperty<'node, B> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> BufferProperty<'node, B> {
+        BufferProperty {
+            raw: RawProperty::new(node_handle, index),
+            brand: PhantomData,
+        }
+    }
+
+    /// Returns the buffer this property is set to, or `None` if it is not set to a buffer.
+    pub fn get(&self) -> Result<Option<buffer::Buffer<'node, B>>> {
+        let mut buffer_handle: libaudioverse_sys::LavHandle = 0;
+        check(unsafe {
+            libaudioverse_sys::Lav_nodeGetBufferProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut buffer_handle,
+            )
+        })?;
+        if buffer_handle == 0 {
+            return Ok(None);
+        }
+        Ok(Some(buffer::Buffer::from_ptr(LavPtr::new(buffer_handle)?)))
+    }
+
+    pub fn set(&self, buffer: &buffer::Buffer<'_, B>) -> Result<()> {
         check(unsafe {
             libaudioverse_sys::Lav_nodeSetBufferProperty(
-                self.node_handle,
-                self.index,
-                buffer.handle,
+                self.raw.node_handle,
+                self.raw.index,
+                *buffer.handle,
             )
         })?;
         Ok(())
     }
 }
+
+impl<'node, B> Property<Option<buffer::Buffer<'node, B>>> for BufferProperty<'node, B> {
+    fn get(&self) -> Result<Option<buffer::Buffer<'node, B>>> {
+        BufferProperty::get(self)
+    }
+
+    /// Sets this property to a buffer, or clears it if `value` is `None`.
+    fn set(&self, value: Option<buffer::Buffer<'node, B>>) -> Result<()> {
+        match value {
+            Some(buffer) => BufferProperty::set(self, &buffer),
+            None => check(unsafe {
+                libaudioverse_sys::Lav_nodeSetBufferProperty(
+                    self.raw.node_handle,
+                    self.raw.index,
+                    0,
+                )
+            }),
+        }
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
+
+#[test]
+fn returns_the_buffer_it_was_set_to() {
+    use nodes::BufferNode;
+    use {Libaudioverse, Server};
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    let node = BufferNode::new(&server).unwrap();
+    assert!(node.buffer().get().unwrap().is_none());
+    let buffer = buffer::Buffer::new(&server).unwrap();
+    node.buffer().set(&buffer).unwrap();
+    let current = node.buffer().get().unwrap().unwrap();
+    assert_eq!(*current.handle, *buffer.handle);
+}
@@ -1,5 +1,6 @@
 use super::super::super::libaudioverse_sys;
-use super::super::super::{Error, Result};
+use super::super::super::{Error, ErrorKind, Result};
+use super::property::{Property, RawProperty};
 use check;
 use std::os::raw::c_int;
 
@@ -16,16 +17,28 @@ pub enum DistanceModel {
 }
 
 /// Proxy to a DistanceModel property.
-pub struct DistanceModelProperty {
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+pub struct DistanceModelProperty<'node> {
+    pub(crate) raw: RawProperty<'node>,
 }
 
-impl DistanceModelProperty {
+impl<'node> DistanceModelProperty<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> DistanceModelProperty<'node> {
+        DistanceModelProperty {
+            raw: RawProperty::new(node_handle, index),
+        }
+    }
+
     pub fn get(&self) -> Result<DistanceModel> {
         let mut value: i32 = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_nodeGetIntProperty(self.node_handle, self.index, &mut value)
+            libaudioverse_sys::Lav_nodeGetIntProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut value,
+            )
         })?;
         match value {
             libaudioverse_sys::Lav_DISTANCE_MODELS_Lav_DISTANCE_MODEL_INVERSE => {
@@ -37,16 +50,13 @@ impl DistanceModelProperty {
             libaudioverse_sys::Lav_DISTANCE_MODELS_Lav_DISTANCE_MODEL_LINEAR => {
                 Ok(DistanceModel::Linear)
             }
-            _ => Err(Error {
-                code: libaudioverse_sys::Lav_ERRORS_Lav_ERROR_UNKNOWN,
-                message: "Invalid distance model".to_string(),
-            }),
+            _ => Err(Error::new(ErrorKind::Unknown, "Invalid distance model")),
         }
     }
 
     fn set_int(&self, value: i32) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_nodeSetIntProperty(self.node_handle, self.index, value)
+            libaudioverse_sys::Lav_nodeSetIntProperty(self.raw.node_handle, self.raw.index, value)
         })?;
         Ok(())
     }
@@ -69,3 +79,23 @@ impl DistanceModelProperty {
     }
     */
 }
+
+impl<'node> Property<DistanceModel> for DistanceModelProperty<'node> {
+    fn get(&self) -> Result<DistanceModel> {
+        DistanceModelProperty::get(self)
+    }
+
+    fn set(&self, value: DistanceModel) -> Result<()> {
+        DistanceModelProperty::set(self, value)
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
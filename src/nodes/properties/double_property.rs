@@ -1,21 +1,37 @@
 use super::super::super::libaudioverse_sys;
 use super::super::super::Result;
 
+use super::property::{Property, RawProperty};
 use check;
+use server::Unbranded;
+use std::marker::PhantomData;
 use std::os::raw::c_int;
 
-/// Proxy to a double property.
-pub struct DoubleProperty {
-    // allow nodes to construct instances of this struct
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+/// Proxy to a double property. `B` is the brand of the node's server, which must match the brand of nodes connected to this property.
+pub struct DoubleProperty<'node, B = Unbranded> {
+    pub(crate) raw: RawProperty<'node>,
+    brand: PhantomData<B>,
 }
 
-impl DoubleProperty {
+impl<'node, B> DoubleProperty<'node, B> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> DoubleProperty<'node, B> {
+        DoubleProperty {
+            raw: RawProperty::new(node_handle, index),
+            brand: PhantomData,
+        }
+    }
+
     pub fn get(&self) -> Result<f64> {
         let mut value: f64 = 0.0;
         check(unsafe {
-            libaudioverse_sys::Lav_nodeGetDoubleProperty(self.node_handle, self.index, &mut value)
+            libaudioverse_sys::Lav_nodeGetDoubleProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut value,
+            )
         })?;
         Ok(value)
     }
@@ -26,8 +42,8 @@ impl DoubleProperty {
         let mut max: f64 = 0.0;
         check(unsafe {
             libaudioverse_sys::Lav_nodeGetDoublePropertyRange(
-                self.node_handle,
-                self.index,
+                self.raw.node_handle,
+                self.raw.index,
                 &mut min,
                 &mut max,
             )
@@ -37,8 +53,36 @@ impl DoubleProperty {
 
     pub fn set(&self, value: f64) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_nodeSetDoubleProperty(self.node_handle, self.index, value)
+            libaudioverse_sys::Lav_nodeSetDoubleProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                value,
+            )
         })?;
         Ok(())
     }
 }
+
+impl<'node, B> Property<f64> for DoubleProperty<'node, B> {
+    fn get(&self) -> Result<f64> {
+        DoubleProperty::get(self)
+    }
+
+    fn set(&self, value: f64) -> Result<()> {
+        DoubleProperty::set(self, value)
+    }
+
+    fn range(&self) -> Result<Option<(f64, f64)>> {
+        Ok(Some(self.get_range()?))
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
@@ -0,0 +1,157 @@
+use super::super::super::libaudioverse_sys;
+use super::super::super::Result;
+use super::{
+    BufferProperty, DoubleProperty, Float3Property, Float6Property, FloatArrayProperty,
+    FloatProperty, IntArrayProperty, IntProperty, Properties, PropertyInfo, PropertyType,
+    StringProperty,
+};
+use server::Unbranded;
+use std::os::raw::c_int;
+use {Error, ErrorKind};
+
+/// The value of a property, for use with `DynamicProperty`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+    Int(i32),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Float3([f32; 3]),
+    Float6([f32; 6]),
+    FloatArray(Vec<f32>),
+    IntArray(Vec<i32>),
+}
+
+/// Proxy to a property whose type is only known at runtime, as returned by `Node::property`.
+/// Match on this to obtain the typed proxy, or use `get` and `set` to work with `PropertyValue`s.
+pub enum DynamicProperty<'node, B = Unbranded> {
+    Int(IntProperty<'node>),
+    Float(FloatProperty<'node, B>),
+    Double(DoubleProperty<'node, B>),
+    String(StringProperty<'node>),
+    Float3(Float3Property<'node>),
+    Float6(Float6Property<'node>),
+    FloatArray(FloatArrayProperty<'node, B>),
+    IntArray(IntArrayProperty<'node, B>),
+    /// Buffer properties cannot be accessed through `get` and `set`; use the `BufferProperty` directly.
+    Buffer(BufferProperty<'node, B>),
+}
+
+impl<'node, B> DynamicProperty<'node, B> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+        property_type: PropertyType,
+    ) -> DynamicProperty<'node, B> {
+        match property_type {
+            PropertyType::Int => DynamicProperty::Int(IntProperty::new(node_handle, index)),
+            PropertyType::Float => DynamicProperty::Float(FloatProperty::new(node_handle, index)),
+            PropertyType::Double => {
+                DynamicProperty::Double(DoubleProperty::new(node_handle, index))
+            }
+            PropertyType::String => {
+                DynamicProperty::String(StringProperty::new(node_handle, index))
+            }
+            PropertyType::Float3 => {
+                DynamicProperty::Float3(Float3Property::new(node_handle, index))
+            }
+            PropertyType::Float6 => {
+                DynamicProperty::Float6(Float6Property::new(node_handle, index))
+            }
+            PropertyType::FloatArray => {
+                DynamicProperty::FloatArray(FloatArrayProperty::new(node_handle, index))
+            }
+            PropertyType::IntArray => {
+                DynamicProperty::IntArray(IntArrayProperty::new(node_handle, index))
+            }
+            PropertyType::Buffer => {
+                DynamicProperty::Buffer(BufferProperty::new(node_handle, index))
+            }
+        }
+    }
+
+    /// Looks up the property of a node by name.
+    pub(crate) fn find(
+        node_handle: libaudioverse_sys::LavHandle,
+        name: &str,
+    ) -> Result<DynamicProperty<'node, B>> {
+        for info in Properties::new(node_handle) {
+            let info: PropertyInfo = info?;
+            if info.name == name {
+                return Ok(DynamicProperty::new(
+                    node_handle,
+                    info.index,
+                    info.property_type,
+                ));
+            }
+        }
+        Err(Error::new(
+            ErrorKind::InvalidProperty,
+            &format!("The node has no property named {}", name),
+        ))
+    }
+
+    /// Returns the current value of this property.
+    pub fn get(&self) -> Result<PropertyValue> {
+        Ok(match self {
+            DynamicProperty::Int(p) => PropertyValue::Int(p.get()?),
+            DynamicProperty::Float(p) => PropertyValue::Float(p.get()?),
+            DynamicProperty::Double(p) => PropertyValue::Double(p.get()?),
+            DynamicProperty::String(p) => PropertyValue::String(p.get()?),
+            DynamicProperty::Float3(p) => PropertyValue::Float3(p.get()?),
+            DynamicProperty::Float6(p) => PropertyValue::Float6(p.get()?),
+            DynamicProperty::FloatArray(p) => PropertyValue::FloatArray(p.get()?),
+            DynamicProperty::IntArray(p) => PropertyValue::IntArray(p.get()?),
+            DynamicProperty::Buffer(_) => return Err(unsupported()),
+        })
+    }
+
+    /// Sets the value of this property. It is an error if the value is of a different type than the property.
+    pub fn set(&self, value: PropertyValue) -> Result<()> {
+        match (self, value) {
+            (DynamicProperty::Int(p), PropertyValue::Int(v)) => p.set(v),
+            (DynamicProperty::Float(p), PropertyValue::Float(v)) => p.set(v),
+            (DynamicProperty::Double(p), PropertyValue::Double(v)) => p.set(v),
+            (DynamicProperty::String(p), PropertyValue::String(v)) => p.set(&v),
+            (DynamicProperty::Float3(p), PropertyValue::Float3(v)) => p.set(v[0], v[1], v[2]),
+            (DynamicProperty::Float6(p), PropertyValue::Float6(v)) => {
+                p.set(v[0], v[1], v[2], v[3], v[4], v[5])
+            }
+            (DynamicProperty::FloatArray(p), PropertyValue::FloatArray(v)) => p.replace(&v),
+            (DynamicProperty::IntArray(p), PropertyValue::IntArray(v)) => p.replace(&v),
+            (DynamicProperty::Buffer(_), _) => Err(unsupported()),
+            _ => Err(Error::new(
+                ErrorKind::TypeMismatch,
+                "The value is of a different type than the property",
+            )),
+        }
+    }
+}
+
+fn unsupported() -> Error {
+    Error::new(
+        ErrorKind::TypeMismatch,
+        "This property cannot be accessed through a PropertyValue",
+    )
+}
+
+#[test]
+fn lists_and_sets_properties_by_name() {
+    use nodes::{GainNode, Node};
+    use {Libaudioverse, Server};
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    let node = GainNode::new(&server, 2).unwrap();
+    let mul = node
+        .properties()
+        .map(|info| info.unwrap())
+        .find(|info| info.name == "mul")
+        .expect("gain nodes have a mul property");
+    assert_eq!(mul.property_type, PropertyType::Float);
+
+    let property = node.property("mul").unwrap();
+    property.set(PropertyValue::Float(0.5)).unwrap();
+    assert_eq!(property.get().unwrap(), PropertyValue::Float(0.5));
+    assert!(property.set(PropertyValue::Int(1)).is_err());
+}
@@ -1,40 +1,33 @@
 use super::super::super::libaudioverse_sys;
 use super::super::super::Result;
 
+use super::property::{Property, RawProperty};
 use check;
 use std::os::raw::c_int;
 
 /// Proxy to a float3 property.
-pub struct Float3Property {
-    // allow Server to construct instances of this struct
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+pub struct Float3Property<'node> {
+    pub(crate) raw: RawProperty<'node>,
 }
 
-impl Float3Property {
+impl<'node> Float3Property<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> Float3Property<'node> {
+        Float3Property {
+            raw: RawProperty::new(node_handle, index),
+        }
+    }
+
     pub fn get(&self) -> Result<[f32; 3]> {
-        let mut values: [f32; 3] = [0.0, 0.0, 0.0];
+        let mut values = [0.0; 3];
         check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                0,
+            libaudioverse_sys::Lav_nodeGetFloat3Property(
+                self.raw.node_handle,
+                self.raw.index,
                 &mut values[0],
-            )
-        })?;
-        check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                1,
                 &mut values[1],
-            )
-        })?;
-        check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                2,
                 &mut values[2],
             )
         })?;
@@ -43,8 +36,34 @@ impl Float3Property {
 
     pub fn set(&self, v1: f32, v2: f32, v3: f32) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_nodeSetFloat3Property(self.node_handle, self.index, v1, v2, v3)
+            libaudioverse_sys::Lav_nodeSetFloat3Property(
+                self.raw.node_handle,
+                self.raw.index,
+                v1,
+                v2,
+                v3,
+            )
         })?;
         Ok(())
     }
 }
+
+impl<'node> Property<[f32; 3]> for Float3Property<'node> {
+    fn get(&self) -> Result<[f32; 3]> {
+        Float3Property::get(self)
+    }
+
+    fn set(&self, value: [f32; 3]) -> Result<()> {
+        Float3Property::set(self, value[0], value[1], value[2])
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
@@ -1,63 +1,36 @@
 use super::super::super::libaudioverse_sys;
 use super::super::super::Result;
 
+use super::property::{Property, RawProperty};
 use check;
 use std::os::raw::c_int;
 
 /// Proxy to a float6 property.
-pub struct Float6Property {
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+pub struct Float6Property<'node> {
+    pub(crate) raw: RawProperty<'node>,
 }
 
-impl Float6Property {
+impl<'node> Float6Property<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> Float6Property<'node> {
+        Float6Property {
+            raw: RawProperty::new(node_handle, index),
+        }
+    }
+
     pub fn get(&self) -> Result<[f32; 6]> {
-        let mut values: [f32; 6] = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let mut values = [0.0; 6];
         check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                0,
+            libaudioverse_sys::Lav_nodeGetFloat6Property(
+                self.raw.node_handle,
+                self.raw.index,
                 &mut values[0],
-            )
-        })?;
-        check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                1,
                 &mut values[1],
-            )
-        })?;
-        check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                2,
                 &mut values[2],
-            )
-        })?;
-        check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                3,
                 &mut values[3],
-            )
-        })?;
-        check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                4,
                 &mut values[4],
-            )
-        })?;
-        check(unsafe {
-            libaudioverse_sys::Lav_nodeReadFloatArrayProperty(
-                self.node_handle,
-                self.index,
-                5,
                 &mut values[5],
             )
         })?;
@@ -67,8 +40,8 @@ impl Float6Property {
     pub fn set(&self, v1: f32, v2: f32, v3: f32, v4: f32, v5: f32, v6: f32) -> Result<()> {
         check(unsafe {
             libaudioverse_sys::Lav_nodeSetFloat6Property(
-                self.node_handle,
-                self.index,
+                self.raw.node_handle,
+                self.raw.index,
                 v1,
                 v2,
                 v3,
@@ -80,3 +53,25 @@ impl Float6Property {
         Ok(())
     }
 }
+
+impl<'node> Property<[f32; 6]> for Float6Property<'node> {
+    fn get(&self) -> Result<[f32; 6]> {
+        Float6Property::get(self)
+    }
+
+    fn set(&self, value: [f32; 6]) -> Result<()> {
+        Float6Property::set(
+            self, value[0], value[1], value[2], value[3], value[4], value[5],
+        )
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
@@ -0,0 +1,21 @@
+use super::super::super::libaudioverse_sys;
+use super::super::super::Result;
+
+use super::array_property;
+use super::property::{Property, RawProperty};
+use check;
+use server::Unbranded;
+use std::marker::PhantomData;
+use std::os::raw::{c_int, c_uint};
+
+array_property! {
+    /// Proxy to a float array property.
+    FloatArrayProperty,
+    f32,
+    0.0,
+    c_uint,
+    Lav_nodeReplaceFloatArrayProperty,
+    Lav_nodeReadFloatArrayProperty,
+    Lav_nodeWriteFloatArrayProperty,
+    Lav_nodeGetFloatArrayPropertyLength
+}
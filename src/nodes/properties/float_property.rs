@@ -1,21 +1,37 @@
 use super::super::super::libaudioverse_sys;
 use super::super::super::Result;
 
+use super::property::{Property, RawProperty};
 use check;
+use server::Unbranded;
+use std::marker::PhantomData;
 use std::os::raw::c_int;
 
-/// Proxy to a float property.
-pub struct FloatProperty {
-    // allow Server to construct instances of this struct
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+/// Proxy to a float property. `B` is the brand of the node's server, which must match the brand of nodes connected to this property.
+pub struct FloatProperty<'node, B = Unbranded> {
+    pub(crate) raw: RawProperty<'node>,
+    brand: PhantomData<B>,
 }
 
-impl FloatProperty {
+impl<'node, B> FloatProperty<'node, B> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> FloatProperty<'node, B> {
+        FloatProperty {
+            raw: RawProperty::new(node_handle, index),
+            brand: PhantomData,
+        }
+    }
+
     pub fn get(&self) -> Result<f32> {
         let mut value: f32 = 0.0;
         check(unsafe {
-            libaudioverse_sys::Lav_nodeGetFloatProperty(self.node_handle, self.index, &mut value)
+            libaudioverse_sys::Lav_nodeGetFloatProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut value,
+            )
         })?;
         Ok(value)
     }
@@ -26,8 +42,8 @@ impl FloatProperty {
         let mut max: f32 = 0.0;
         check(unsafe {
             libaudioverse_sys::Lav_nodeGetFloatPropertyRange(
-                self.node_handle,
-                self.index,
+                self.raw.node_handle,
+                self.raw.index,
                 &mut min,
                 &mut max,
             )
@@ -37,8 +53,32 @@ impl FloatProperty {
 
     pub fn set(&self, value: f32) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_nodeSetFloatProperty(self.node_handle, self.index, value)
+            libaudioverse_sys::Lav_nodeSetFloatProperty(self.raw.node_handle, self.raw.index, value)
         })?;
         Ok(())
     }
 }
+
+impl<'node, B> Property<f32> for FloatProperty<'node, B> {
+    fn get(&self) -> Result<f32> {
+        FloatProperty::get(self)
+    }
+
+    fn set(&self, value: f32) -> Result<()> {
+        FloatProperty::set(self, value)
+    }
+
+    fn range(&self) -> Result<Option<(f32, f32)>> {
+        Ok(Some(self.get_range()?))
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
@@ -0,0 +1,21 @@
+use super::super::super::libaudioverse_sys;
+use super::super::super::Result;
+
+use super::array_property;
+use super::property::{Property, RawProperty};
+use check;
+use server::Unbranded;
+use std::marker::PhantomData;
+use std::os::raw::{c_int, c_uint};
+
+array_property! {
+    /// Proxy to an int array property.
+    IntArrayProperty,
+    i32,
+    0,
+    c_int,
+    Lav_nodeReplaceIntArrayProperty,
+    Lav_nodeReadIntArrayProperty,
+    Lav_nodeWriteIntArrayProperty,
+    Lav_nodeGetIntArrayPropertyLength
+}
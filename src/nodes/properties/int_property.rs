@@ -1,21 +1,33 @@
 use super::super::super::libaudioverse_sys;
 use super::super::super::Result;
 
+use super::property::{Property, RawProperty};
 use check;
 use std::os::raw::c_int;
 
 /// Proxy to an int property.
-pub struct IntProperty {
-    // allow Server to construct instances of this struct
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+pub struct IntProperty<'node> {
+    pub(crate) raw: RawProperty<'node>,
 }
 
-impl IntProperty {
+impl<'node> IntProperty<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> IntProperty<'node> {
+        IntProperty {
+            raw: RawProperty::new(node_handle, index),
+        }
+    }
+
     pub fn get(&self) -> Result<i32> {
         let mut value: i32 = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_nodeGetIntProperty(self.node_handle, self.index, &mut value)
+            libaudioverse_sys::Lav_nodeGetIntProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut value,
+            )
         })?;
         Ok(value)
     }
@@ -26,8 +38,8 @@ impl IntProperty {
         let mut max: i32 = 0;
         check(unsafe {
             libaudioverse_sys::Lav_nodeGetIntPropertyRange(
-                self.node_handle,
-                self.index,
+                self.raw.node_handle,
+                self.raw.index,
                 &mut min,
                 &mut max,
             )
@@ -37,8 +49,32 @@ impl IntProperty {
 
     pub fn set(&self, value: i32) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_nodeSetIntProperty(self.node_handle, self.index, value)
+            libaudioverse_sys::Lav_nodeSetIntProperty(self.raw.node_handle, self.raw.index, value)
         })?;
         Ok(())
     }
 }
+
+impl<'node> Property<i32> for IntProperty<'node> {
+    fn get(&self) -> Result<i32> {
+        IntProperty::get(self)
+    }
+
+    fn set(&self, value: i32) -> Result<()> {
+        IntProperty::set(self, value)
+    }
+
+    fn range(&self) -> Result<Option<(i32, i32)>> {
+        Ok(Some(self.get_range()?))
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
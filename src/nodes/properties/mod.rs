@@ -2,27 +2,43 @@
 //! Properties control aspects of nodes in the manner that their name suggests.
 //!
 
+#[macro_use]
+mod array_property;
+mod automatable;
 mod bool_property;
 mod buffer_property;
 mod distance_model_property;
 mod double_property;
+mod dynamic_property;
 mod float3_property;
+mod float_array_property;
 mod float6_property;
 mod float_property;
+mod int_array_property;
 mod int_property;
 mod node_state_property;
 mod panning_strategy_property;
+mod property;
+mod property_info;
+mod string_property;
 
 #[doc(inline)]
 pub use self::{
+    automatable::Automatable,
     bool_property::BoolProperty,
     buffer_property::BufferProperty,
     distance_model_property::{DistanceModel, DistanceModelProperty},
     double_property::DoubleProperty,
+    dynamic_property::{DynamicProperty, PropertyValue},
     float3_property::Float3Property,
+    float_array_property::FloatArrayProperty,
     float6_property::Float6Property,
     float_property::FloatProperty,
+    int_array_property::IntArrayProperty,
     int_property::IntProperty,
     node_state_property::{NodeState, NodeStateProperty},
     panning_strategy_property::{PanningStrategy, PanningStrategyProperty},
+    property::Property,
+    property_info::{Properties, PropertyInfo, PropertyRange, PropertyType},
+    string_property::StringProperty,
 };
@@ -1,5 +1,6 @@
 use super::super::super::libaudioverse_sys;
-use super::super::super::{Error, Result};
+use super::super::super::{Error, ErrorKind, Result};
+use super::property::{Property, RawProperty};
 use check;
 use std::os::raw::c_int;
 
@@ -15,17 +16,28 @@ pub enum NodeState {
 }
 
 /// Proxy to a NodeState property.
-pub struct NodeStateProperty {
-    // allow Server to construct instances of this struct
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+pub struct NodeStateProperty<'node> {
+    pub(crate) raw: RawProperty<'node>,
 }
 
-impl NodeStateProperty {
+impl<'node> NodeStateProperty<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> NodeStateProperty<'node> {
+        NodeStateProperty {
+            raw: RawProperty::new(node_handle, index),
+        }
+    }
+
     pub fn get(&self) -> Result<NodeState> {
         let mut value: i32 = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_nodeGetIntProperty(self.node_handle, self.index, &mut value)
+            libaudioverse_sys::Lav_nodeGetIntProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut value,
+            )
         })?;
         match value {
             libaudioverse_sys::Lav_NODE_STATES_Lav_NODESTATE_ALWAYS_PLAYING => {
@@ -33,16 +45,13 @@ impl NodeStateProperty {
             }
             libaudioverse_sys::Lav_NODE_STATES_Lav_NODESTATE_PLAYING => Ok(NodeState::Playing),
             libaudioverse_sys::Lav_NODE_STATES_Lav_NODESTATE_PAUSED => Ok(NodeState::Paused),
-            _ => Err(Error {
-                code: libaudioverse_sys::Lav_ERRORS_Lav_ERROR_UNKNOWN,
-                message: "Invalid node state".to_string(),
-            }),
+            _ => Err(Error::new(ErrorKind::Unknown, "Invalid node state")),
         }
     }
 
     fn set_int(&self, value: i32) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_nodeSetIntProperty(self.node_handle, self.index, value)
+            libaudioverse_sys::Lav_nodeSetIntProperty(self.raw.node_handle, self.raw.index, value)
         })?;
         Ok(())
     }
@@ -65,3 +74,23 @@ impl NodeStateProperty {
     }
     */
 }
+
+impl<'node> Property<NodeState> for NodeStateProperty<'node> {
+    fn get(&self) -> Result<NodeState> {
+        NodeStateProperty::get(self)
+    }
+
+    fn set(&self, value: NodeState) -> Result<()> {
+        NodeStateProperty::set(self, value)
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
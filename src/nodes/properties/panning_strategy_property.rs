@@ -1,5 +1,6 @@
 use super::super::super::libaudioverse_sys;
-use super::super::super::{Error, Result};
+use super::super::super::{Error, ErrorKind, Result};
+use super::property::{Property, RawProperty};
 use check;
 use std::os::raw::c_int;
 
@@ -19,16 +20,28 @@ pub enum PanningStrategy {
 }
 
 /// Proxy to a PanningStrategy property.
-pub struct PanningStrategyProperty {
-    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
-    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+pub struct PanningStrategyProperty<'node> {
+    pub(crate) raw: RawProperty<'node>,
 }
 
-impl PanningStrategyProperty {
+impl<'node> PanningStrategyProperty<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> PanningStrategyProperty<'node> {
+        PanningStrategyProperty {
+            raw: RawProperty::new(node_handle, index),
+        }
+    }
+
     pub fn get(&self) -> Result<PanningStrategy> {
         let mut value: i32 = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_nodeGetIntProperty(self.node_handle, self.index, &mut value)
+            libaudioverse_sys::Lav_nodeGetIntProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut value,
+            )
         })?;
         match value {
             libaudioverse_sys::Lav_PANNING_STRATEGIES_Lav_PANNING_STRATEGY_HRTF => {
@@ -46,16 +59,13 @@ impl PanningStrategyProperty {
             libaudioverse_sys::Lav_PANNING_STRATEGIES_Lav_PANNING_STRATEGY_SURROUND71 => {
                 Ok(PanningStrategy::Surround71)
             }
-            _ => Err(Error {
-                code: libaudioverse_sys::Lav_ERRORS_Lav_ERROR_UNKNOWN,
-                message: "Invalid panning strategy".to_string(),
-            }),
+            _ => Err(Error::new(ErrorKind::Unknown, "Invalid panning strategy")),
         }
     }
 
     fn set_int(&self, value: i32) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_nodeSetIntProperty(self.node_handle, self.index, value)
+            libaudioverse_sys::Lav_nodeSetIntProperty(self.raw.node_handle, self.raw.index, value)
         })?;
         Ok(())
     }
@@ -64,3 +74,23 @@ impl PanningStrategyProperty {
         self.set_int(strategy as i32)
     }
 }
+
+impl<'node> Property<PanningStrategy> for PanningStrategyProperty<'node> {
+    fn get(&self) -> Result<PanningStrategy> {
+        PanningStrategyProperty::get(self)
+    }
+
+    fn set(&self, value: PanningStrategy) -> Result<()> {
+        PanningStrategyProperty::set(self, value)
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
@@ -0,0 +1,97 @@
+use super::super::super::libaudioverse_sys;
+use super::super::super::Result;
+use super::property_info;
+use check;
+use lav_ptr::LavPtr;
+use server::{AnyMode, Server};
+use std::marker::PhantomData;
+use std::os::raw::c_int;
+
+/// Identifies one property of one node. Every property proxy wraps one of these.
+#[derive(Clone, Copy)]
+pub(crate) struct RawProperty<'node> {
+    pub(crate) index: c_int, // the index libaudioverse uses to identify this property for this node
+    pub(crate) node_handle: libaudioverse_sys::LavHandle, // a handle to the parent node
+    node: PhantomData<&'node ()>, // prevents this proxy from outliving the parent node
+}
+
+impl<'node> RawProperty<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> RawProperty<'node> {
+        RawProperty {
+            index,
+            node_handle,
+            node: PhantomData,
+        }
+    }
+
+    /// Returns the server the node belongs to.
+    pub(crate) fn server(&self) -> Result<Server<'node, AnyMode>> {
+        let mut server_handle: libaudioverse_sys::LavHandle = 0;
+        check(unsafe {
+            libaudioverse_sys::Lav_nodeGetServer(self.node_handle, &mut server_handle)
+        })?;
+        Ok(Server {
+            handle: LavPtr::new(server_handle)?,
+            lib: PhantomData,
+            mode: PhantomData,
+            brand: PhantomData,
+        })
+    }
+}
+
+/// Functionality shared by every property proxy, allowing generic code to work with properties of any type.
+/// The proxies also provide these operations as inherent methods, so this trait only needs to be imported by generic code.
+pub trait Property<T> {
+    /// Returns the value of this property.
+    fn get(&self) -> Result<T>;
+
+    /// Sets the value of this property.
+    fn set(&self, value: T) -> Result<()>;
+
+    /// Returns the minimum and maximum values of this property, or `None` if properties of this type do not have a range.
+    fn range(&self) -> Result<Option<(T, T)>> {
+        Ok(None)
+    }
+
+    /// Resets this property to its default value.
+    fn reset(&self) -> Result<()> {
+        check(unsafe { libaudioverse_sys::Lav_nodeResetProperty(self.node_handle(), self.index()) })
+    }
+
+    /// Returns the name of this property, as used in the Libaudioverse manual.
+    fn name(&self) -> Result<String> {
+        property_info::property_name(self.node_handle(), self.index())
+    }
+
+    /// Returns whether this property is read-only.
+    fn is_read_only(&self) -> Result<bool> {
+        property_info::is_read_only(self.node_handle(), self.index())
+    }
+
+    /// Returns the handle of the node this property belongs to.
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle;
+
+    /// Returns the index Libaudioverse uses to identify this property.
+    #[doc(hidden)]
+    fn index(&self) -> c_int;
+}
+
+#[test]
+fn resets_properties_to_their_default() {
+    use nodes::{GainNode, Node};
+    use {Libaudioverse, Server};
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    let node = GainNode::new(&server, 2).unwrap();
+    let mul = node.mul();
+    Property::set(&mul, 0.25).unwrap();
+    mul.reset().unwrap();
+    assert_eq!(mul.get().unwrap(), 1.0);
+    assert_eq!(mul.name().unwrap(), "mul");
+    assert!(!mul.is_read_only().unwrap());
+}
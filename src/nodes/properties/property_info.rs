@@ -0,0 +1,226 @@
+use super::super::super::libaudioverse_sys;
+use super::super::super::Result;
+use check;
+use nodes::NodeType;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use {Error, ErrorKind};
+
+// Libaudioverse has no way to list the properties of a node, so `Properties` probes every index in this range.
+// Properties shared by every node use indices from -100 downwards, while node-specific properties use small indices, some negative and some not.
+// Every index in the Libaudioverse headers lies within -102..=13, so this range leaves room for properties added by newer versions while keeping a probe to a few hundred calls.
+const PROBED_INDICES: Range<c_int> = -300..64;
+
+// Libaudioverse has no way to ask whether a property is read-only, so the properties its documentation marks as read-only are listed here.
+const READ_ONLY: &[(NodeType, c_int)] = &[(
+    NodeType::FileStreamerNode,
+    libaudioverse_sys::Lav_FILE_STREAMER_PROPERTIES_Lav_FILE_STREAMER_ENDED,
+)];
+
+/// The type of a property, which determines the proxy used to access it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PropertyType {
+    Int,
+    Float,
+    Double,
+    String,
+    Float3,
+    Float6,
+    FloatArray,
+    IntArray,
+    Buffer,
+}
+
+impl PropertyType {
+    fn from_raw(property_type: c_int) -> Option<PropertyType> {
+        Some(match property_type {
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_INT => PropertyType::Int,
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_FLOAT => PropertyType::Float,
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_DOUBLE => PropertyType::Double,
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_STRING => PropertyType::String,
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_FLOAT3 => PropertyType::Float3,
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_FLOAT6 => PropertyType::Float6,
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_FLOAT_ARRAY => {
+                PropertyType::FloatArray
+            }
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_INT_ARRAY => {
+                PropertyType::IntArray
+            }
+            libaudioverse_sys::Lav_PROPERTY_TYPES_Lav_PROPERTYTYPE_BUFFER => PropertyType::Buffer,
+            _ => return None,
+        })
+    }
+}
+
+/// The range of values a property accepts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PropertyRange {
+    Int(i32, i32),
+    Float(f32, f32),
+    Double(f64, f64),
+}
+
+/// Describes one of the properties of a node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyInfo {
+    /// The index Libaudioverse uses to identify the property.
+    pub index: i32,
+    /// The name of the property, as used in the Libaudioverse manual.
+    pub name: String,
+    /// The type of the property.
+    pub property_type: PropertyType,
+    /// The range of the property at the time it was queried. This is `None` for types which do not have a range.
+    pub range: Option<PropertyRange>,
+    /// Whether the range of the property can change, for example because it depends on the length of a buffer.
+    pub has_dynamic_range: bool,
+    /// Whether the property is read-only.
+    pub read_only: bool,
+}
+
+impl PropertyInfo {
+    /// Queries the property with the specified index, returning `None` if the node does not have it.
+    pub(crate) fn query(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> Result<Option<PropertyInfo>> {
+        let mut raw_type: c_int = 0;
+        match check(unsafe {
+            libaudioverse_sys::Lav_nodeGetPropertyType(node_handle, index, &mut raw_type)
+        }) {
+            Err(ref e) if e.kind == ErrorKind::InvalidProperty => return Ok(None),
+            result => result?,
+        }
+        let property_type = PropertyType::from_raw(raw_type)
+            .ok_or_else(|| Error::new(ErrorKind::Unknown, "Unknown property type"))?;
+
+        let name = property_name(node_handle, index)?;
+
+        let mut has_dynamic_range: c_int = 0;
+        check(unsafe {
+            libaudioverse_sys::Lav_nodeGetPropertyHasDynamicRange(
+                node_handle,
+                index,
+                &mut has_dynamic_range,
+            )
+        })?;
+
+        Ok(Some(PropertyInfo {
+            index,
+            name,
+            property_type,
+            range: query_range(node_handle, index, property_type)?,
+            has_dynamic_range: has_dynamic_range != 0,
+            read_only: is_read_only(node_handle, index)?,
+        }))
+    }
+}
+
+/// Returns the name of a property.
+pub(crate) fn property_name(
+    node_handle: libaudioverse_sys::LavHandle,
+    index: c_int,
+) -> Result<String> {
+    let mut raw_name: *mut c_char = ptr::null_mut();
+    check(unsafe {
+        libaudioverse_sys::Lav_nodeGetPropertyName(node_handle, index, &mut raw_name)
+    })?;
+    unsafe {
+        let name = CStr::from_ptr(raw_name).to_string_lossy().into_owned();
+        libaudioverse_sys::Lav_free(raw_name as *mut _);
+        Ok(name)
+    }
+}
+
+/// Returns whether a property is read-only, without writing to it.
+pub(crate) fn is_read_only(
+    node_handle: libaudioverse_sys::LavHandle,
+    index: c_int,
+) -> Result<bool> {
+    let mut object_type: c_int = 0;
+    check(unsafe { libaudioverse_sys::Lav_handleGetType(node_handle, &mut object_type) })?;
+    Ok(matches!(
+        NodeType::from_raw(object_type),
+        Some(node_type) if READ_ONLY.contains(&(node_type, index))
+    ))
+}
+
+fn query_range(
+    node_handle: libaudioverse_sys::LavHandle,
+    index: c_int,
+    property_type: PropertyType,
+) -> Result<Option<PropertyRange>> {
+    Ok(match property_type {
+        PropertyType::Int => {
+            let (mut min, mut max): (c_int, c_int) = (0, 0);
+            check(unsafe {
+                libaudioverse_sys::Lav_nodeGetIntPropertyRange(
+                    node_handle,
+                    index,
+                    &mut min,
+                    &mut max,
+                )
+            })?;
+            Some(PropertyRange::Int(min, max))
+        }
+        PropertyType::Float => {
+            let (mut min, mut max): (f32, f32) = (0.0, 0.0);
+            check(unsafe {
+                libaudioverse_sys::Lav_nodeGetFloatPropertyRange(
+                    node_handle,
+                    index,
+                    &mut min,
+                    &mut max,
+                )
+            })?;
+            Some(PropertyRange::Float(min, max))
+        }
+        PropertyType::Double => {
+            let (mut min, mut max): (f64, f64) = (0.0, 0.0);
+            check(unsafe {
+                libaudioverse_sys::Lav_nodeGetDoublePropertyRange(
+                    node_handle,
+                    index,
+                    &mut min,
+                    &mut max,
+                )
+            })?;
+            Some(PropertyRange::Double(min, max))
+        }
+        _ => None,
+    })
+}
+
+/// Iterator over the properties of a node, returned by `Node::properties`.
+pub struct Properties<'node> {
+    node_handle: libaudioverse_sys::LavHandle,
+    indices: Range<c_int>,
+    node: PhantomData<&'node ()>, // prevents this iterator from outliving the node
+}
+
+impl<'node> Properties<'node> {
+    pub(crate) fn new(node_handle: libaudioverse_sys::LavHandle) -> Properties<'node> {
+        Properties {
+            node_handle,
+            indices: PROBED_INDICES,
+            node: PhantomData,
+        }
+    }
+}
+
+impl<'node> Iterator for Properties<'node> {
+    type Item = Result<PropertyInfo>;
+
+    fn next(&mut self) -> Option<Result<PropertyInfo>> {
+        for index in &mut self.indices {
+            match PropertyInfo::query(self.node_handle, index) {
+                Ok(None) => continue,
+                Ok(Some(info)) => return Some(Ok(info)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,71 @@
+use super::super::super::libaudioverse_sys;
+use super::super::super::Result;
+
+use super::property::{Property, RawProperty};
+use check;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+/// Proxy to a string property.
+pub struct StringProperty<'node> {
+    pub(crate) raw: RawProperty<'node>,
+}
+
+impl<'node> StringProperty<'node> {
+    pub(crate) fn new(
+        node_handle: libaudioverse_sys::LavHandle,
+        index: c_int,
+    ) -> StringProperty<'node> {
+        StringProperty {
+            raw: RawProperty::new(node_handle, index),
+        }
+    }
+
+    pub fn get(&self) -> Result<String> {
+        let mut value: *const c_char = ptr::null();
+        check(unsafe {
+            libaudioverse_sys::Lav_nodeGetStringProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                &mut value,
+            )
+        })?;
+        // the string belongs to the node, so it is copied before the property can change
+        Ok(unsafe { CStr::from_ptr(value) }
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    /// Sets the value of this property. It is an error for the value to contain NUL bytes.
+    pub fn set(&self, value: &str) -> Result<()> {
+        let value = CString::new(value)?;
+        check(unsafe {
+            libaudioverse_sys::Lav_nodeSetStringProperty(
+                self.raw.node_handle,
+                self.raw.index,
+                value.as_ptr() as *mut _,
+            )
+        })
+    }
+}
+
+impl<'node> Property<String> for StringProperty<'node> {
+    fn get(&self) -> Result<String> {
+        StringProperty::get(self)
+    }
+
+    fn set(&self, value: String) -> Result<()> {
+        StringProperty::set(self, &value)
+    }
+
+    #[doc(hidden)]
+    fn node_handle(&self) -> libaudioverse_sys::LavHandle {
+        self.raw.node_handle
+    }
+
+    #[doc(hidden)]
+    fn index(&self) -> c_int {
+        self.raw.index
+    }
+}
@@ -1,7 +1,11 @@
 use super::super::{libaudioverse_sys, server};
-use super::Node;
+use super::{Node, NodeType, TypedNode};
+use callbacks::{self, Callback};
 use check;
-use std::mem;
+use lav_ptr::LavPtr;
+use registry::{self, Slot};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 use std::os::raw::c_void;
 use std::slice;
 use Result;
@@ -15,29 +19,58 @@ use Result;
 /// index | channels | description
 /// ------|----------|------------
 /// 0 | Depends on arguments to this node’s constructor. | The result of the configured callback.
+pub struct PullNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
+}
 
-pub struct PullNode<'node> {
-    handle: libaudioverse_sys::LavHandle,
-    audio_callback: Option<Box<Box<'node + FnMut(&PullNode, i32, i32, &mut [f32])>>>,
+impl<'server, B> Clone for PullNode<'server, B> {
+    fn clone(&self) -> PullNode<'server, B> {
+        PullNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
 }
 
-impl<'node> Node for PullNode<'node> {
+impl<'server, B> Node<B> for PullNode<'server, B> {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for PullNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::PullNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> PullNode<'server, B> {
+        PullNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
         self.handle
     }
 }
 
-impl<'node> PullNode<'node> {
+impl<'server, B> PullNode<'server, B> {
     /// Creates a new Pull node.
-    pub fn new(server: &server::Server, sr: u32, channels: u32) -> Result<PullNode> {
+    pub fn new<M>(
+        server: &server::Server<'server, M, B>,
+        sr: u32,
+        channels: u32,
+    ) -> Result<PullNode<'server, B>> {
         let mut node_handle: libaudioverse_sys::LavHandle = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_createPullNode(server.handle, sr, channels, &mut node_handle)
+            libaudioverse_sys::Lav_createPullNode(*server.handle, sr, channels, &mut node_handle)
         })?;
         Ok(PullNode {
-            handle: node_handle,
-            audio_callback: None,
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
         })
     }
 
@@ -51,38 +84,51 @@ impl<'node> PullNode<'node> {
     /// frames: i32 | The number of frames of audio needed. This is not guaranteed to be the same on every call.
     /// channels: i32 | The number of channels as set when the pull node is created.
     /// buffer: &mut [f32] | The destination to which audio should be written. This is a buffer that is frames*channels long. Write interleaved audio data to it and use zeroes when the callback has less data than requested. Do not assume that it is zeroed.
-    pub fn set_audio_callback<F>(&mut self, callback: F) -> Result<()>
+    ///
+    /// The callback is called from the audio thread, and is kept until it is replaced or Libaudioverse destroys this node. If it panics, the node outputs silence for that block.
+    pub fn set_audio_callback<F>(&self, callback: F) -> Result<()>
     where
-        F: 'node + FnMut(&PullNode, i32, i32, &mut [f32]),
+        F: 'static + Send + FnMut(&PullNode<'_, B>, i32, i32, &mut [f32]),
     {
-        let cb: Box<Box<FnMut(&PullNode, i32, i32, &mut [f32])>> = Box::new(Box::new(callback));
-        let cb_ptr = Box::into_raw(cb);
-        self.audio_callback = Some(unsafe { Box::from_raw(cb_ptr) });
+        let callback = Callback::new(Box::new(callback));
         check(unsafe {
             libaudioverse_sys::Lav_pullNodeSetAudioCallback(
-                self.handle,
-                Some(callback_handler),
-                cb_ptr as *mut _,
+                *self.handle,
+                Some(callback_handler::<B, F>),
+                callback.userdata(),
             )
         })?;
+        // the previous callback, if any, is freed once it can no longer be running
+        registry::insert(*self.handle, Slot::PullAudio, Box::new(callback));
         Ok(())
     }
 }
 
 /// Handles callbacks from Libaudioverse, allowing closures to be used as callbacks.
-extern "C" fn callback_handler(
+extern "C" fn callback_handler<B, F>(
     node_handle: libaudioverse_sys::LavHandle,
     frames: i32,
     channels: i32,
     buffer: *mut f32,
     userdata: *mut c_void,
-) {
+) where
+    F: FnMut(&PullNode<'_, B>, i32, i32, &mut [f32]),
+{
     let buf = unsafe { slice::from_raw_parts_mut(buffer, (frames * channels) as usize) };
-    let closure: &mut Box<FnMut(&PullNode, i32, i32, &mut [f32])> =
-        unsafe { mem::transmute(userdata) };
-    let node = PullNode {
-        handle: node_handle,
-        audio_callback: None,
+    // the node is only borrowed for the duration of the callback, so it must not release its reference when it goes out of scope
+    let node = ManuallyDrop::new(PullNode::<B> {
+        handle: LavPtr {
+            handle: node_handle,
+        },
+        server: PhantomData,
+    });
+    let result = unsafe {
+        callbacks::invoke::<F, _, _>(userdata, |closure| closure(&node, frames, channels, buf))
     };
-    closure(&node, frames, channels, buf)
+    if result.is_none() {
+        // the closure panicked, possibly after writing part of the buffer
+        for sample in buf.iter_mut() {
+            *sample = 0.0;
+        }
+    }
 }
@@ -1,8 +1,12 @@
 use super::super::{libaudioverse_sys, server};
 use super::properties::FloatProperty;
-use super::Node;
+use super::{Node, NodeType, TypedNode};
+use callbacks::{self, Callback};
 use check;
-use std::mem;
+use lav_ptr::LavPtr;
+use registry::{self, Slot};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 use std::os::raw::c_void;
 use Result;
 
@@ -15,30 +19,58 @@ use Result;
 /// index | channels | description
 /// ------|----------|------------
 /// 0 | Depends on arguments to this node’s constructor. | Either audio from the internal queue or zero.
-pub struct PushNode<'node> {
-    handle: libaudioverse_sys::LavHandle,
-    low_callback: Option<Box<Box<'node + FnMut(&mut PushNode)>>>,
-    underrun_callback: Option<Box<Box<'node + FnMut(&mut PushNode)>>>,
+pub struct PushNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
 }
 
-impl<'node> Node for PushNode<'node> {
+impl<'server, B> Clone for PushNode<'server, B> {
+    fn clone(&self) -> PushNode<'server, B> {
+        PushNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for PushNode<'server, B> {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for PushNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::PushNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> PushNode<'server, B> {
+        PushNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
         self.handle
     }
 }
 
-impl<'node> PushNode<'node> {
+impl<'server, B> PushNode<'server, B> {
     /// Creates a new push node.
-    pub fn new(server: &server::Server, sr: u32, channels: u32) -> Result<PushNode> {
+    pub fn new<M>(
+        server: &server::Server<'server, M, B>,
+        sr: u32,
+        channels: u32,
+    ) -> Result<PushNode<'server, B>> {
         let mut node_handle: libaudioverse_sys::LavHandle = 0;
         check(unsafe {
-            libaudioverse_sys::Lav_createPushNode(server.handle, sr, channels, &mut node_handle)
+            libaudioverse_sys::Lav_createPushNode(*server.handle, sr, channels, &mut node_handle)
         })?;
         Ok(PushNode {
-            handle: node_handle,
-            low_callback: None,
-            underrun_callback: None,
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
         })
     }
 
@@ -51,65 +83,74 @@ impl<'node> PushNode<'node> {
     /// Rate: k
     ///
     /// When the remaining audio in the push node has a duration less than this property, the low callback is called.
-    pub fn threshold(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_PUSH_NODE_PROPERTIES_Lav_PUSH_THRESHOLD,
-            node_handle: self.handle,
-        }
+    pub fn threshold(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_PUSH_NODE_PROPERTIES_Lav_PUSH_THRESHOLD,
+        )
     }
 
     /// Sets the low callback, which is Called once per block and outside the audio thread when there is less than the specified threshold audio remaining.
-    pub fn set_low_callback<F>(&mut self, callback: F) -> Result<()>
+    /// The callback is called from a thread owned by Libaudioverse, and is kept until it is replaced or Libaudioverse destroys this node.
+    pub fn set_low_callback<F>(&self, callback: F) -> Result<()>
     where
-        F: 'node + FnMut(&mut PushNode),
+        F: 'static + Send + FnMut(&PushNode<'_, B>),
     {
-        let cb: Box<Box<FnMut(&mut PushNode)>> = Box::new(Box::new(callback));
-        let cb_ptr = Box::into_raw(cb);
-        self.low_callback = Some(unsafe { Box::from_raw(cb_ptr) });
+        let callback = Callback::new(Box::new(callback));
         check(unsafe {
             libaudioverse_sys::Lav_pushNodeSetLowCallback(
-                self.handle,
-                Some(callback_handler),
-                cb_ptr as *mut _,
+                *self.handle,
+                Some(callback_handler::<B, F>),
+                callback.userdata(),
             )
         })?;
+        // the previous callback, if any, is freed once it can no longer be running
+        registry::insert(*self.handle, Slot::PushLow, Box::new(callback));
         Ok(())
     }
 
     /// Sets the underrun callback, which is Called exactly once and outside the audio thread when the node runs out of audio completely.
-    pub fn set_underrun_callback<F>(&mut self, callback: F) -> Result<()>
+    /// The callback is called from a thread owned by Libaudioverse, and is kept until it is replaced or Libaudioverse destroys this node.
+    pub fn set_underrun_callback<F>(&self, callback: F) -> Result<()>
     where
-        F: 'node + FnMut(&mut PushNode),
+        F: 'static + Send + FnMut(&PushNode<'_, B>),
     {
-        let cb: Box<Box<FnMut(&mut PushNode)>> = Box::new(Box::new(callback));
-        let cb_ptr = Box::into_raw(cb);
-        self.underrun_callback = Some(unsafe { Box::from_raw(cb_ptr) });
+        let callback = Callback::new(Box::new(callback));
         check(unsafe {
             libaudioverse_sys::Lav_pushNodeSetUnderrunCallback(
-                self.handle,
-                Some(callback_handler),
-                cb_ptr as *mut _,
+                *self.handle,
+                Some(callback_handler::<B, F>),
+                callback.userdata(),
             )
         })?;
+        registry::insert(*self.handle, Slot::PushUnderrun, Box::new(callback));
         Ok(())
     }
 
     /// Feed more audio data into the internal queue.
     pub fn feed(&self, length_in_samples: u32, frames: *mut f32) -> Result<()> {
         check(unsafe {
-            libaudioverse_sys::Lav_pushNodeFeed(self.handle, length_in_samples, frames)
+            libaudioverse_sys::Lav_pushNodeFeed(*self.handle, length_in_samples, frames)
         })?;
         Ok(())
     }
 }
 
 /// Handles callbacks from Libaudioverse, allowing closures to be used as callbacks.
-extern "C" fn callback_handler(node_handle: libaudioverse_sys::LavHandle, userdata: *mut c_void) {
-    let closure: &mut Box<FnMut(&mut PushNode)> = unsafe { mem::transmute(userdata) };
-    let mut node = PushNode {
-        handle: node_handle,
-        low_callback: None,
-        underrun_callback: None,
-    };
-    closure(&mut node)
+extern "C" fn callback_handler<B, F>(
+    node_handle: libaudioverse_sys::LavHandle,
+    userdata: *mut c_void,
+) where
+    F: FnMut(&PushNode<'_, B>),
+{
+    // the node is only borrowed for the duration of the callback, so it must not release its reference when it goes out of scope
+    let node = ManuallyDrop::new(PushNode::<B> {
+        handle: LavPtr {
+            handle: node_handle,
+        },
+        server: PhantomData,
+    });
+    unsafe {
+        callbacks::invoke::<F, _, _>(userdata, |closure| closure(&node));
+    }
 }
@@ -2,8 +2,10 @@ use super::super::{libaudioverse_sys, server};
 use super::properties::{
     BoolProperty, DistanceModelProperty, Float3Property, FloatProperty, PanningStrategyProperty,
 };
-use super::{environment_node, Node};
+use super::{environment_node, Node, NodeType, TypedNode};
 use check;
+use lav_ptr::LavPtr;
+use std::marker::PhantomData;
 use Result;
 
 /// The source node allows the spatialization of sound that passes through it. Sources have one input which is mono, to which a node should be connected. The audio from the input is spatialized according both to the source’s properties and those on its environment, and passed directly to the environment. Sources have no outputs. To hear a source, you must connect its environment to something instead.
@@ -16,33 +18,61 @@ use Result;
 /// 0 | 1 | The audio to enter the 3D environment.
 ///
 /// This node has no outputs.
-pub struct SourceNode {
-    handle: libaudioverse_sys::LavHandle,
+pub struct SourceNode<'server, B = server::Unbranded> {
+    handle: LavPtr,
+    server: PhantomData<(&'server server::Server<'server>, B)>, // nodes cannot outlive the library instance of the server they were created on
 }
 
-impl Node for SourceNode {
+impl<'server, B> Clone for SourceNode<'server, B> {
+    fn clone(&self) -> SourceNode<'server, B> {
+        SourceNode {
+            handle: self.handle.clone(),
+            server: PhantomData,
+        }
+    }
+}
+
+impl<'server, B> Node<B> for SourceNode<'server, B> {
     #[doc(hidden)]
     fn get_handle(&self) -> libaudioverse_sys::LavHandle {
+        *self.handle
+    }
+}
+
+impl<'server, B> TypedNode<'server, B> for SourceNode<'server, B> {
+    const NODE_TYPE: NodeType = NodeType::SourceNode;
+
+    #[doc(hidden)]
+    fn from_ptr(handle: LavPtr) -> SourceNode<'server, B> {
+        SourceNode {
+            handle,
+            server: PhantomData,
+        }
+    }
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> LavPtr {
         self.handle
     }
 }
 
-impl SourceNode {
+impl<'server, B> SourceNode<'server, B> {
     /// Creates a new source node.
-    pub fn new(
-        server: &server::Server,
-        environment_node: &environment_node::EnvironmentNode,
-    ) -> Result<SourceNode> {
+    pub fn new<M>(
+        server: &server::Server<'server, M, B>,
+        environment_node: &environment_node::EnvironmentNode<'server, B>,
+    ) -> Result<SourceNode<'server, B>> {
         let mut node_handle: libaudioverse_sys::LavHandle = 0;
         check(unsafe {
             libaudioverse_sys::Lav_createSourceNode(
-                server.handle,
-                environment_node.handle,
+                *server.handle,
+                *environment_node.handle,
                 &mut node_handle,
             )
         })?;
         Ok(SourceNode {
-            handle: node_handle,
+            handle: LavPtr::new(node_handle)?,
+            server: PhantomData,
         })
     }
 
@@ -51,11 +81,11 @@ impl SourceNode {
     /// Default value: False
     ///
     /// In order to make working with sources easier for simple applications, some properties of source objects are ignored in favor of values on the environment. This property is used to disable this behavior for properties related to the distance model.
-    pub fn control_distance_model(&self) -> BoolProperty {
-        BoolProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_CONTROL_DISTANCE_MODEL,
-            node_handle: self.handle,
-        }
+    pub fn control_distance_model(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_CONTROL_DISTANCE_MODEL,
+        )
     }
 
     /// Returns the control_panning property.
@@ -63,11 +93,11 @@ impl SourceNode {
     /// Default value: False
     ///
     /// In order to make working with sources easier for simple applications, some properties of source objects are ignored in favor of values on the environment. This property is used to disable this behavior for properties related to panning.
-    pub fn control_panning(&self) -> BoolProperty {
-        BoolProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_CONTROL_PANNING,
-            node_handle: self.handle,
-        }
+    pub fn control_panning(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_CONTROL_PANNING,
+        )
     }
 
     /// Returns the control_reverb property.
@@ -75,11 +105,11 @@ impl SourceNode {
     /// Default value: False
     ///
     /// In order to make working with sources easier for simple applications, some properties of source objects are ignored in favor of values on the environment. This property is used to disable this behavior for properties related to reverb.
-    pub fn control_reverb(&self) -> BoolProperty {
-        BoolProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_CONTROL_REVERB,
-            node_handle: self.handle,
-        }
+    pub fn control_reverb(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_CONTROL_REVERB,
+        )
     }
 
     /// Returns the distance_model property.
@@ -89,11 +119,11 @@ impl SourceNode {
     /// Default value: DistanceModel::Linear
     ///
     /// The distance model determines how quickly sources get quieter as they move away from the listener. The default value of this property is set from the corresponding property on the environment at source creation. By default, sources ignore this property in favor of the value provided by their environment. Set Lav_SOURCE_CONTROL_DISTANCE_MODEL to true to control it yourself.
-    pub fn distance_model(&self) -> DistanceModelProperty {
-        DistanceModelProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_DISTANCE_MODEL,
-            node_handle: self.handle,
-        }
+    pub fn distance_model(&self) -> DistanceModelProperty<'_> {
+        DistanceModelProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_DISTANCE_MODEL,
+        )
     }
 
     /// Returns the head_relative property.
@@ -102,11 +132,11 @@ impl SourceNode {
     ///
     /// Whether or not to consider this source’s position to always be relative to the listener.
     /// Sources which are head relative interpret their positions in the default coordinate system, relative to the listener. Positive x is right, positive y is up, and positive z is behind the listener. The orientation and position properties of an environment do not affect head relative sources, making them ideal for such things as footsteps and/or HUD effects that should be panned.
-    pub fn head_relative(&self) -> BoolProperty {
-        BoolProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_HEAD_RELATIVE,
-            node_handle: self.handle,
-        }
+    pub fn head_relative(&self) -> BoolProperty<'_> {
+        BoolProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_HEAD_RELATIVE,
+        )
     }
 
     /// Returns the max_distance property.
@@ -116,11 +146,11 @@ impl SourceNode {
     /// Default value: 150.0
     ///
     /// The maximum distance from the listener at which the source will be audible. The default value of this property is set from the corresponding property on the environment at source creation. By default, sources do not respect this property and use the corresponding value from their environment. Set Lav_SOURCE_CONTROL_DISTANCE_MODEL to true to control it yourself.
-    pub fn max_distance(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_MAX_DISTANCE,
-            node_handle: self.handle,
-        }
+    pub fn max_distance(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_MAX_DISTANCE,
+        )
     }
 
     /// Returns the max_reverb_level property.
@@ -132,11 +162,11 @@ impl SourceNode {
     /// The maximum amount of audio to be diverted to reverb sends, if any.
     /// Behavior is undefined if this property is ever less than Lav_SOURCE_MIN_REVERB_LEVEL.
     /// The default value of this property is set from the corresponding property on the environment at source creation. By default, this property is ignored in favor of the value provided by this source’s environment. Set Lav_SOURCE_CONTROL_REVERB to true to control it yourself.
-    pub fn max_reverb_level(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_MAX_REVERB_LEVEL,
-            node_handle: self.handle,
-        }
+    pub fn max_reverb_level(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_MAX_REVERB_LEVEL,
+        )
     }
 
     /// Returns the min_reverb_level property.
@@ -149,11 +179,11 @@ impl SourceNode {
     /// if a send is configured to be a reverb send, this is the minimum amount of audio that will be diverted to it.
     /// Behavior is undefined if this property is ever greater than the value you give to Lav_SOURCE_MAX_REVERB_LEVEL.
     /// The default value of this property is set from the corresponding property on the environment at source creation. By default, this property is ignored in favor of the value provided by this source’s environment. Set Lav_SOURCE_CONTROL_REVERB to true to control it yourself.
-    pub fn min_reverb_level(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_MIN_REVERB_LEVEL,
-            node_handle: self.handle,
-        }
+    pub fn min_reverb_level(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_MIN_REVERB_LEVEL,
+        )
     }
 
     /// Returns the occlusion property.
@@ -165,11 +195,11 @@ impl SourceNode {
     /// A scalar representing how occluded this source is.
     /// This property controls internal filters of the source that make occluded objects sound muffled. A value of 1.0 is a fully occluded source, which will be all but silent; a value of 0.0 has no effect.
     /// It is extremely difficult to map occlusion to a physical quantity. As a consequence, this property is unitless.
-    pub fn occlusion(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_OCCLUSION,
-            node_handle: self.handle,
-        }
+    pub fn occlusion(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_OCCLUSION,
+        )
     }
 
     /// Returns the panning_strategy property.
@@ -179,11 +209,11 @@ impl SourceNode {
     /// Default value: PanningStrategy::Stereo
     ///
     /// The strategy for the internal multipanner. The default value of this property is set from the corresponding property on the environment at source creation. By default, this property is ignored and sources use the value provided by their environment. Set Lav_SOURCE_CONTROL_PANNING to true to control it yourself.
-    pub fn panning_strategy(&self) -> PanningStrategyProperty {
-        PanningStrategyProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_PANNING_STRATEGY,
-            node_handle: self.handle,
-        }
+    pub fn panning_strategy(&self) -> PanningStrategyProperty<'_> {
+        PanningStrategyProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_PANNING_STRATEGY,
+        )
     }
 
     /// Returns the position property.
@@ -191,11 +221,11 @@ impl SourceNode {
     /// Default value: [0.0, 0.0, 0.0]
     ///
     /// The position of the source in world coordinates.
-    pub fn position(&self) -> Float3Property {
-        Float3Property {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_POSITION,
-            node_handle: self.handle,
-        }
+    pub fn position(&self) -> Float3Property<'_> {
+        Float3Property::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_POSITION,
+        )
     }
 
     /// Returns the reverb_distance property.
@@ -208,11 +238,11 @@ impl SourceNode {
     /// If this source is not feeding any effect sends configured as reverbs, this property has no effect.
     /// For values greater than Lav_SOURCE_MAX_DISTANCE, the source will always be heard at least somewhat in the dry path. Lav_SOURCE_DISTANCE_MODEL controls how this crossfading takes place.
     /// The default value of this property is set from the corresponding property on the environment at source creation. By default, sources ignore this property in favor of the value provided by their environment. Set Lav_SOURCE_CONTROL_REVERB to true to control it yourself.
-    pub fn reverb_distance(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_REVERB_DISTANCE,
-            node_handle: self.handle,
-        }
+    pub fn reverb_distance(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_REVERB_DISTANCE,
+        )
     }
 
     /// Returns the size property.
@@ -223,10 +253,10 @@ impl SourceNode {
     ///
     /// The size of the source. Sources are approximated as spheres. The size is used to determine the closest point on the source to the listener, and is the radius of this sphere. Size currently has no other effect.
     /// The default value of this property is set from the corresponding property on the environment at source creation.
-    pub fn size(&self) -> FloatProperty {
-        FloatProperty {
-            index: libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_SIZE,
-            node_handle: self.handle,
-        }
+    pub fn size(&self) -> FloatProperty<'_, B> {
+        FloatProperty::new(
+            *self.handle,
+            libaudioverse_sys::Lav_SOURCE_PROPERTIES_Lav_SOURCE_SIZE,
+        )
     }
 }
@@ -0,0 +1,101 @@
+//! Rust-side state attached to Libaudioverse handles.
+//!
+//! Closures registered as callbacks, and anything else which must live exactly as long as the object it belongs to, is stored here keyed by handle. Libaudioverse may destroy objects on its own, so entries are released from the handle destroyed callback rather than when the Rust value wrapping a handle is dropped.
+//! Released entries are freed the next time the application creates a server, and when the `Libaudioverse` instance is dropped, but never while a callback is running. Callbacks can replace or clear themselves, so nothing which may be called from a callback frees entries.
+use callbacks;
+use libaudioverse_sys;
+use libaudioverse_sys::LavHandle;
+use std::any::Any;
+use std::collections::HashMap;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Identifies a piece of state attached to a handle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Slot {
+    PushLow,
+    PushUnderrun,
+    PullAudio,
+    Block,
+    /// A callback scheduled with `Server::call_in`, identified by a number unique to the process.
+    Scheduled(u64),
+}
+
+type Entry = Box<Any + Send>;
+type Entries = HashMap<Slot, Entry>;
+type HandleDestroyedHook = Arc<Fn(LavHandle) + Send + Sync>;
+
+static REGISTRY: Mutex<Option<HashMap<LavHandle, Entries>>> = Mutex::new(None);
+/// Entries belonging to destroyed handles. Dropping them may call into Libaudioverse, which is not allowed from the handle destroyed callback, so this is done later from an ordinary thread.
+static GRAVEYARD: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+static HOOK: Mutex<Option<HandleDestroyedHook>> = Mutex::new(None);
+
+// a panic while a lock is held cannot leave the maps in an inconsistent state, so poisoning is ignored
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Attaches `value` to `handle`, retiring whatever was previously stored in the same slot.
+/// A callback which is replaced may still be running, possibly because it is the one replacing itself, so it is freed later rather than here. This is safe to call from Libaudioverse's threads.
+pub(crate) fn insert(handle: LavHandle, slot: Slot, value: Entry) {
+    let previous = lock(&REGISTRY)
+        .get_or_insert_with(HashMap::new)
+        .entry(handle)
+        .or_default()
+        .insert(slot, value);
+    if let Some(previous) = previous {
+        lock(&GRAVEYARD).push(previous);
+    }
+}
+
+/// Detaches the value stored in `slot` of `handle` without freeing anything else, which is safe to call from Libaudioverse's threads.
+pub(crate) fn detach(handle: LavHandle, slot: Slot) -> Option<Entry> {
+    lock(&REGISTRY)
+        .as_mut()
+        .and_then(|map| map.get_mut(&handle))
+        .and_then(|entries| entries.remove(&slot))
+}
+
+/// Detaches the value stored in `slot` of `handle` and frees it later, like the state of a destroyed handle. This is safe to call from Libaudioverse's threads.
+pub(crate) fn retire(handle: LavHandle, slot: Slot) {
+    if let Some(entry) = detach(handle, slot) {
+        lock(&GRAVEYARD).push(entry);
+    }
+}
+
+/// Frees the state of handles which have been destroyed, and the values retired, since the last call.
+/// Nothing is freed while a callback is running, since it may be one of the retired closures. This must only be called from the application's threads.
+pub(crate) fn collect_garbage() {
+    if callbacks::running() {
+        return;
+    }
+    // take the entries out first: dropping them may destroy more handles, which adds to the graveyard
+    let dead = mem::take(&mut *lock(&GRAVEYARD));
+    drop(dead);
+}
+
+/// Sets the application's hook, which is called with the raw handle of every object Libaudioverse destroys.
+pub(crate) fn set_hook(hook: Option<HandleDestroyedHook>) {
+    *lock(&HOOK) = hook;
+}
+
+/// Registers `handle_destroyed` with Libaudioverse.
+pub(crate) fn install() {
+    unsafe {
+        libaudioverse_sys::Lav_setHandleDestroyedCallback(Some(handle_destroyed));
+    }
+}
+
+unsafe extern "C" fn handle_destroyed(handle: LavHandle) {
+    let entries = lock(&REGISTRY).as_mut().and_then(|map| map.remove(&handle));
+    if let Some(entries) = entries {
+        lock(&GRAVEYARD).extend(entries.into_values());
+    }
+
+    let hook = lock(&HOOK).clone();
+    if let Some(hook) = hook {
+        // panics must not unwind into Libaudioverse
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(handle)));
+    }
+}
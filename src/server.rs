@@ -1,72 +1,721 @@
 //! The main entry point to Libaudioverse.
 use super::libaudioverse_sys;
 use super::*;
+use callbacks::{self, Callback};
 use check;
+use lav_ptr::LavPtr;
+use registry::{self, Slot};
 use std::ffi::CString;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::os::raw::{c_int, c_uint, c_void};
+use std::path::Path;
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
-/// Represents a server, the main entry point to Libaudioverse. All libaudioverse nodes must be passed a server at creation time as the first argument to their constructor and cannot migrate between them. Furthermore, it is an error to try to connect objects from different servers.
-/// By default, Libaudioverse will use one thread per core on the current system for audio mixing. This may be changed via Lav_serverSetThreads.
+type BlockCallbackFn = FnMut(&Server<AnyMode>, f64) + Send;
+type BlockCallback = Callback<BlockCallbackFn>;
+type ScheduledFn = FnOnce(&Server<AnyMode>, f64) + Send;
+
+/// A closure scheduled with `Server::call_in`, which is taken out when it is called.
+struct Scheduled {
+    id: u64,
+    in_audio_thread: bool,
+    closure: Option<Box<ScheduledFn>>,
+}
+
+/// Distinguishes the callbacks scheduled on the same server.
+static NEXT_SCHEDULED_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Represents a server, the main entry point to Libaudioverse. Servers are created from a `Libaudioverse` instance and cannot outlive it. All libaudioverse nodes must be passed a server at creation time as the first argument to their constructor and cannot migrate between them. Furthermore, it is an error to try to connect objects from different servers.
+/// Nodes and buffers cannot outlive the `Libaudioverse` instance of the server they were created on, so the compiler guarantees that they are dropped before the library is deinitialized. Each of them keeps its server alive inside Libaudioverse, and they do not borrow the `Server` value itself, so a graph survives switching the server between modes. Connecting nodes which belong to two different servers is rejected by the compiler for servers given a brand with `branded`, and reported by Libaudioverse at runtime otherwise.
+/// By default, Libaudioverse will use one thread per core on the current system for audio mixing. This may be changed via `set_threads`.
 /// For full details of this class, see the Libaudioverse manual.
-pub struct Server {
-    pub(crate) handle: libaudioverse_sys::LavHandle,
+///
+/// A server either plays audio through an output device, or is rendered by the application; the two cannot be mixed, so the mode is part of the type. `Server<Realtime>` can select output devices, and `Server<Offline>` can be rendered with `get_block`. Use `into_offline` and `into_realtime` to switch between them. Servers cannot be cloned, so no other value can still claim the old mode after a switch.
+///
+/// `B` is the brand of the server. Servers are created `Unbranded`, and nodes of unbranded servers can be connected to each other even if they belong to different servers, which Libaudioverse reports at runtime. Use `branded` to give a server a brand of its own, so that such connections are rejected by the compiler instead.
+pub struct Server<'lib, M = Realtime, B = Unbranded> {
+    pub(crate) handle: LavPtr,
+    pub(crate) lib: PhantomData<&'lib Libaudioverse>, // servers cannot outlive the library instance they were created from
+    pub(crate) mode: PhantomData<M>,
+    pub(crate) brand: PhantomData<B>,
 }
 
-impl Server {
-    /// Creates a new server with a default sampling rate of 44,100 and a block size of 1024. This is sufficient and performant for most applications.
-    pub fn new() -> Result<Server> {
-        Server::construct(44100, 1024)
-    }
+/// Marks a server which plays audio through an output device.
+pub enum Realtime {}
 
-    /// create a new server with the specified sampling rate and block size. The block size is the number of samples to process at once, and must be a multiple of 4.
-    pub fn construct(sampling_rate: u32, block_size: u32) -> Result<Server> {
+/// Marks a server whose audio is rendered by the application, without an output device.
+pub enum Offline {}
+
+/// Marks a server whose mode is not known, such as the one returned by `Node::get_server`. Only the operations available in both modes can be used.
+pub enum AnyMode {}
+
+/// Marks a server which has not been given a brand with `Server::branded`, and the nodes, properties and buffers belonging to it.
+#[derive(Clone, Copy)]
+pub enum Unbranded {}
+
+/// The brand of a server passed to the closure given to `Server::branded`. Each call creates a distinct `'id`, so the nodes, properties and buffers of different branded servers have different types.
+#[derive(Clone, Copy)]
+pub struct Brand<'id> {
+    // invariant, so that two brands can never be shortened to the same lifetime
+    id: PhantomData<fn(&'id ()) -> &'id ()>,
+}
+
+impl<'lib, M> Server<'lib, M> {
+    fn create(sampling_rate: u32, block_size: u32) -> Result<Server<'lib, M>> {
+        registry::collect_garbage();
         let mut handle: libaudioverse_sys::LavHandle = 0;
         check(unsafe {
             libaudioverse_sys::Lav_createServer(sampling_rate, block_size, &mut handle)
         })?;
-        Ok(Server { handle })
+        Ok(Server {
+            handle: LavPtr::new(handle)?,
+            lib: PhantomData,
+            mode: PhantomData,
+            brand: PhantomData,
+        })
+    }
+
+    /// Gives this server a brand unique to this call, and passes it to `f`.
+    /// Nodes, properties and buffers created while the server is branded carry its brand in their type, so connecting them to those of another branded server is a compile-time error rather than an error of kind `ErrorKind::CannotCrossServers`. Nothing carrying the brand can be returned from `f`.
+    ///
+    /// ```no_run
+    /// # use libaudioverse::{Libaudioverse, Server};
+    /// # use libaudioverse::nodes::{GainNode, Node};
+    /// let lib = Libaudioverse::new().unwrap();
+    /// Server::new(&lib).unwrap().branded(|server| {
+    ///     let gain = GainNode::new(&server, 2).unwrap();
+    ///     gain.connect_server(0).unwrap();
+    /// });
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use libaudioverse::{Libaudioverse, Server};
+    /// # use libaudioverse::nodes::{GainNode, Node};
+    /// let lib = Libaudioverse::new().unwrap();
+    /// Server::new(&lib).unwrap().branded(|first| {
+    ///     Server::new(&lib).unwrap().branded(|second| {
+    ///         let a = GainNode::new(&first, 2).unwrap();
+    ///         let b = GainNode::new(&second, 2).unwrap();
+    ///         a.connect(0, &b, 0).unwrap();
+    ///     })
+    /// });
+    /// ```
+    pub fn branded<F, R>(self, f: F) -> R
+    where
+        F: for<'id> FnOnce(Server<'lib, M, Brand<'id>>) -> R,
+    {
+        f(Server {
+            handle: self.handle,
+            lib: PhantomData,
+            mode: PhantomData,
+            brand: PhantomData,
+        })
+    }
+}
+
+impl<'lib, M, B> Server<'lib, M, B> {
+    fn into_mode<N>(self) -> Server<'lib, N, B> {
+        Server {
+            handle: self.handle,
+            lib: PhantomData,
+            mode: PhantomData,
+            brand: PhantomData,
+        }
+    }
+
+    /// Returns the block size of the server, which is the number of frames mixed at once.
+    pub fn get_block_size(&self) -> Result<u32> {
+        let mut block_size: c_int = 0;
+        check(unsafe { Lav_serverGetBlockSize(*self.handle, &mut block_size) })?;
+        Ok(block_size as u32)
+    }
+
+    /// Returns the sampling rate of the server.
+    pub fn get_sampling_rate(&self) -> Result<u32> {
+        let mut sampling_rate: c_int = 0;
+        check(unsafe { Lav_serverGetSr(*self.handle, &mut sampling_rate) })?;
+        Ok(sampling_rate as u32)
+    }
+
+    /// Returns the number of threads the server uses for mixing.
+    pub fn get_threads(&self) -> Result<u32> {
+        let mut threads: c_int = 0;
+        check(unsafe { Lav_serverGetThreads(*self.handle, &mut threads) })?;
+        Ok(threads as u32)
+    }
+
+    /// Sets the number of threads the server uses for mixing. More threads help large graphs of nodes keep up, at the cost of CPU time spent coordinating them; 1 is often best for small graphs.
+    pub fn set_threads(&self, threads: u32) -> Result<()> {
+        check(unsafe { Lav_serverSetThreads(*self.handle, threads as c_int) })
+    }
+
+    /// Lock the server, preventing the audio thread from mixing until the returned guard is dropped.
+    /// Every change made while the lock is held becomes audible in the same block, which makes it possible to update many properties atomically. Hold the lock only briefly: audio will glitch if it prevents the mixer from keeping up.
+    pub fn lock(&self) -> Result<ServerLock<'_>> {
+        check(unsafe { Lav_serverLock(*self.handle) })?;
+        Ok(ServerLock {
+            handle: *self.handle,
+            server: PhantomData,
+        })
+    }
+
+    /// Run the provided closure with the server locked, so that all changes it makes are applied to the same block of audio.
+    /// The server is unlocked when the closure returns, even if it panics.
+    pub fn batch<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce() -> R,
+    {
+        let _lock = self.lock()?;
+        Ok(f())
+    }
+
+    /// Sets a closure which is called before every block is mixed, with the server and its current time in seconds.
+    /// The closure is called in the audio thread, so changes it makes apply to the block which is about to be mixed. It must return quickly, since mixing waits for it.
+    /// The closure is kept until it is replaced or cleared, or Libaudioverse destroys the server. It may replace or clear itself.
+    pub fn set_block_callback<F>(&self, callback: F) -> Result<()>
+    where
+        F: 'static + Send + FnMut(&Server<AnyMode>, f64),
+    {
+        let callback: BlockCallback = Callback::new(Box::new(callback));
+        check(unsafe {
+            Lav_serverSetBlockCallback(
+                *self.handle,
+                Some(block_callback_handler),
+                callback.userdata(),
+            )
+        })?;
+        // the previous callback, if any, is freed once it can no longer be running
+        registry::insert(*self.handle, Slot::Block, Box::new(callback));
+        Ok(())
+    }
+
+    /// Removes the closure set with `set_block_callback`.
+    pub fn clear_block_callback(&self) -> Result<()> {
+        check(unsafe { Lav_serverSetBlockCallback(*self.handle, None, ptr::null_mut()) })?;
+        // this may be called by the closure itself, so it is freed later
+        registry::retire(*self.handle, Slot::Block);
+        Ok(())
+    }
+
+    /// Schedules a closure to be called once, `delay` from now in server time, with the server and its current time in seconds.
+    /// If `in_audio_thread` is true, the closure is called in the audio thread before the block in which `delay` elapses is mixed, so changes it makes apply to that block; it must return quickly. Otherwise it is called from a thread owned by Libaudioverse, which is less precise but allows blocking.
+    /// If Libaudioverse destroys the server first, the closure is dropped without being called.
+    /// The closure is dropped by the thread which calls it as soon as it returns, so a closure called in the audio thread should not own anything which is slow to drop. The record Libaudioverse was given for it is freed at the same time, except in the audio thread, where that is left until the application next creates a server or drops the `Libaudioverse` instance.
+    pub fn call_in<F>(&self, delay: Duration, in_audio_thread: bool, callback: F) -> Result<()>
+    where
+        F: 'static + Send + FnOnce(&Server<AnyMode>, f64),
+    {
+        let id = NEXT_SCHEDULED_ID.fetch_add(1, Ordering::Relaxed);
+        let callback = Callback::new(Box::new(Scheduled {
+            id,
+            in_audio_thread,
+            closure: Some(Box::new(callback)),
+        }));
+        let userdata = callback.userdata();
+        // registered first, since the closure may be called before Lav_serverCallIn returns
+        registry::insert(*self.handle, Slot::Scheduled(id), Box::new(callback));
+        let result = check(unsafe {
+            Lav_serverCallIn(
+                *self.handle,
+                delay.as_secs_f64(),
+                in_audio_thread as c_int,
+                Some(scheduled_handler),
+                userdata,
+            )
+        });
+        if result.is_err() {
+            // Libaudioverse never received the closure, so nothing can be running it
+            drop(registry::detach(*self.handle, Slot::Scheduled(id)));
+        }
+        result
+    }
+}
+
+impl<'lib> Server<'lib, Realtime> {
+    /// Creates a new server with a default sampling rate of 44,100 and a block size of 1024. This is sufficient and performant for most applications.
+    pub fn new(lib: &'lib Libaudioverse) -> Result<Server<'lib, Realtime>> {
+        Server::construct(lib, 44100, 1024)
     }
 
+    /// create a new server with the specified sampling rate and block size. The block size is the number of samples to process at once, and must be a multiple of 4.
+    pub fn construct(
+        _lib: &'lib Libaudioverse,
+        sampling_rate: u32,
+        block_size: u32,
+    ) -> Result<Server<'lib, Realtime>> {
+        Server::create(sampling_rate, block_size)
+    }
+}
+
+impl<'lib, B> Server<'lib, Realtime, B> {
     /// Set the output  of the server to the system's default audio device with 2 channels and 2 mixahead.
     pub fn set_output_device(&self) -> Result<()> {
-        let default = CString::new("default").unwrap();
-        self.set_output_device_details(&default, 2, 2)
+        self.set_default_output_device(2, 2)
+    }
+
+    /// Set the output of the server to the system's default audio device with the specified number of channels and mixahead.
+    pub fn set_default_output_device(&self, channels: i32, mixahead: i32) -> Result<()> {
+        self.set_output_device_by_identifier("default", channels, mixahead)
     }
 
-    /// Set the output device of the server.
-    /// Use the literal string "default" for the default audio device. Note that it is possible to change the output device of a server even after it has been set.
-    /// After the output device has been set, calls to Lav_serverGetBlock will error.
+    /// Set the output device of the server to one of the devices returned by `devices(&lib)`.
+    /// `channels` may be less than the number of channels the device supports. Note that it is possible to change the output device of a server even after it has been set.
     pub fn set_output_device_details(
         &self,
-        identifier: &CString,
+        device: &Device,
+        channels: i32,
+        mixahead: i32,
+    ) -> Result<()> {
+        self.set_output_device_by_identifier(&device.identifier, channels, mixahead)
+    }
+
+    fn set_output_device_by_identifier(
+        &self,
+        identifier: &str,
         channels: i32,
         mixahead: i32,
     ) -> Result<()> {
+        let identifier = CString::new(identifier)?;
+        check(unsafe {
+            Lav_serverSetOutputDevice(*self.handle, identifier.as_ptr(), channels, mixahead)
+        })
+    }
+
+    /// Stops playing audio through the output device, so that the server can be rendered with `get_block`.
+    /// Nodes and buffers which were created on the server remain connected and keep working.
+    pub fn into_offline(self) -> Result<Server<'lib, Offline, B>> {
+        check(unsafe { Lav_serverClearOutputDevice(*self.handle) })?;
+        Ok(self.into_mode())
+    }
+
+    /*
+      pub fn create_buffer(&self) -> Result<buffer::Buffer> {
+          let mut buf_handle : libaudioverse_sys::LavHandle = 0;
+          check(unsafe { Lav_createBuffer(self.handle, &mut buf_handle) })?;
+          Ok(buffer::Buffer { handle : buf_handle })
+      }
+
+      pub fn create_buffer_node(&self) -> Result<buffer_node::BufferNode> {
+          let mut node_handle : libaudioverse_sys::LavHandle = 0;
+          check(unsafe { libaudioverse_sys::Lav_createBufferNode(self.handle, &mut node_handle) })?;
+          Ok(buffer_node::BufferNode { handle : node_handle })
+      }
+    */
+}
+
+impl<'lib> Server<'lib, Offline> {
+    /// Creates a new server for offline rendering, with a sampling rate of 44,100 and a block size of 1024.
+    pub fn new_offline(lib: &'lib Libaudioverse) -> Result<Server<'lib, Offline>> {
+        Server::construct_offline(lib, 44100, 1024)
+    }
+
+    /// Creates a new server for offline rendering with the specified sampling rate and block size. The block size must be a multiple of 4.
+    pub fn construct_offline(
+        _lib: &'lib Libaudioverse,
+        sampling_rate: u32,
+        block_size: u32,
+    ) -> Result<Server<'lib, Offline>> {
+        Server::create(sampling_rate, block_size)
+    }
+}
+
+impl<'lib, B> Server<'lib, Offline, B> {
+    /// Mixes the next block of audio into `buffer` as interleaved samples, advancing the server by one block. This is how audio is rendered without an audio device.
+    /// `buffer` must hold exactly one block of `channels` channels. If `apply_mixing_matrix` is true, the output of the server is converted to `channels` channels with the mixing matrices Libaudioverse uses for audio devices.
+    pub fn get_block(
+        &self,
+        channels: u32,
+        apply_mixing_matrix: bool,
+        buffer: &mut [f32],
+    ) -> Result<()> {
+        if buffer.len() != self.get_block_size()? as usize * channels as usize {
+            return Err(Error::new(
+                ErrorKind::Range,
+                "The buffer must hold exactly one block of audio",
+            ));
+        }
+        check(unsafe {
+            Lav_serverGetBlock(
+                *self.handle,
+                channels as c_uint,
+                apply_mixing_matrix as c_int,
+                buffer.as_mut_ptr(),
+            )
+        })
+    }
+
+    /// Returns an endless iterator over the blocks of audio mixed by the server, as interleaved samples with the mixing matrix applied.
+    pub fn blocks(&self, channels: u32) -> Blocks<'_, 'lib, B> {
+        Blocks {
+            server: self,
+            channels,
+        }
+    }
+
+    /// Renders `duration` of audio without an audio device, returning it as interleaved samples with the mixing matrix applied.
+    /// The server mixes whole blocks, so it is advanced to the end of the block containing the last returned frame.
+    pub fn render(&self, channels: u32, duration: Duration) -> Result<Vec<f32>> {
+        let frames = (duration.as_secs_f64() * self.get_sampling_rate()? as f64).round() as usize;
+        let samples = frames * channels as usize;
+        let mut rendered = Vec::with_capacity(samples);
+        for block in self.blocks(channels) {
+            if rendered.len() >= samples {
+                break;
+            }
+            rendered.extend(block?);
+        }
+        rendered.truncate(samples);
+        Ok(rendered)
+    }
+
+    /// Renders `duration` of audio to the file at `path`, advancing the server as if `get_block` had been called.
+    /// With the `wav` feature enabled, `export_wav` can be used instead to choose the sample format and report progress.
+    pub fn write_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        channels: u32,
+        duration: Duration,
+        apply_mixing_matrix: bool,
+    ) -> Result<()> {
+        let path = path_to_cstring(path.as_ref())?;
         check(unsafe {
-            Lav_serverSetOutputDevice(self.handle, identifier.as_ptr(), channels, mixahead)
+            Lav_serverWriteFile(
+                *self.handle,
+                path.as_ptr(),
+                channels as c_int,
+                duration.as_secs_f64(),
+                apply_mixing_matrix as c_int,
+            )
         })
     }
 
-    /* 
-    pub fn create_buffer(&self) -> Result<buffer::Buffer> {
-        let mut buf_handle : libaudioverse_sys::LavHandle = 0;
-        check(unsafe { Lav_createBuffer(self.handle, &mut buf_handle) })?;
-        Ok(buffer::Buffer { handle : buf_handle })
+    /// Converts this server into one which can select an output device. Nothing is audible until a device is set.
+    /// Nodes and buffers which were created on the server remain connected and keep working.
+    pub fn into_realtime(self) -> Server<'lib, Realtime, B> {
+        self.into_mode()
+    }
+}
+
+/// Configures and creates servers, as an alternative to the constructors of `Server`.
+///
+/// ```no_run
+/// # use libaudioverse::{Libaudioverse, ServerBuilder};
+/// let lib = Libaudioverse::new().unwrap();
+/// let server = ServerBuilder::new()
+///     .sampling_rate(48000)
+///     .block_size(256)
+///     .threads(2)
+///     .build(&lib)
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ServerBuilder {
+    sampling_rate: u32,
+    block_size: u32,
+    threads: Option<u32>,
+    device: Option<Device>,
+    channels: i32,
+    mixahead: i32,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> ServerBuilder {
+        ServerBuilder::new()
+    }
+}
+
+impl ServerBuilder {
+    /// Starts with the settings used by `Server::new` and `Server::set_output_device`: a sampling rate of 44,100, a block size of 1024, the default number of threads, and the default output device with 2 channels and 2 mixahead.
+    pub fn new() -> ServerBuilder {
+        ServerBuilder {
+            sampling_rate: 44100,
+            block_size: 1024,
+            threads: None,
+            device: None,
+            channels: 2,
+            mixahead: 2,
+        }
+    }
+
+    /// Sets the sampling rate of the server.
+    pub fn sampling_rate(mut self, sampling_rate: u32) -> ServerBuilder {
+        self.sampling_rate = sampling_rate;
+        self
+    }
+
+    /// Sets the block size, which is the number of frames mixed at once. It must be a multiple of 4. Smaller blocks reduce latency, but cost more CPU time.
+    pub fn block_size(mut self, block_size: u32) -> ServerBuilder {
+        self.block_size = block_size;
+        self
+    }
+
+    /// Sets the number of threads used for mixing.
+    pub fn threads(mut self, threads: u32) -> ServerBuilder {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Sets the output device used by `build`. Without this, the system's default audio device is used.
+    pub fn device(mut self, device: Device) -> ServerBuilder {
+        self.device = Some(device);
+        self
+    }
+
+    /// Sets the number of channels to output to the device.
+    pub fn channels(mut self, channels: i32) -> ServerBuilder {
+        self.channels = channels;
+        self
+    }
+
+    /// Sets the number of blocks mixed ahead of the audio device. Higher values protect against glitches when the mixer falls behind, at the cost of latency.
+    pub fn mixahead(mut self, mixahead: i32) -> ServerBuilder {
+        self.mixahead = mixahead;
+        self
+    }
+
+    /// Creates the server and starts playing audio through the output device.
+    pub fn build<'lib>(&self, lib: &'lib Libaudioverse) -> Result<Server<'lib, Realtime>> {
+        let server = self.create(lib)?;
+        match self.device {
+            Some(ref device) => {
+                server.set_output_device_details(device, self.channels, self.mixahead)?
+            }
+            None => server.set_default_output_device(self.channels, self.mixahead)?,
+        }
+        Ok(server)
+    }
+
+    /// Creates a server for offline rendering. The device, channel and mixahead settings are not used.
+    pub fn build_offline<'lib>(&self, lib: &'lib Libaudioverse) -> Result<Server<'lib, Offline>> {
+        self.create(lib)
+    }
+
+    fn create<'lib, M>(&self, _lib: &'lib Libaudioverse) -> Result<Server<'lib, M>> {
+        if self.block_size == 0 || !self.block_size.is_multiple_of(4) {
+            return Err(Error::new(
+                ErrorKind::Range,
+                "The block size must be a positive multiple of 4",
+            ));
+        }
+        let server = Server::create(self.sampling_rate, self.block_size)?;
+        if let Some(threads) = self.threads {
+            server.set_threads(threads)?;
+        }
+        Ok(server)
+    }
+}
+
+/// Wraps the handle of a server which Libaudioverse has passed to a callback, without taking a reference to it.
+fn borrowed_server<'a>(server_handle: LavHandle) -> ManuallyDrop<Server<'a, AnyMode>> {
+    ManuallyDrop::new(Server {
+        handle: LavPtr {
+            handle: server_handle,
+        },
+        lib: PhantomData,
+        mode: PhantomData,
+        brand: PhantomData,
+    })
+}
+
+/// Handles block callbacks from Libaudioverse, passing them on to the closure set with `set_block_callback`.
+extern "C" fn block_callback_handler(server_handle: LavHandle, time: f64, userdata: *mut c_void) {
+    let server = borrowed_server(server_handle);
+    unsafe {
+        callbacks::invoke::<BlockCallbackFn, _, _>(userdata, |closure| closure(&server, time));
+    }
+}
+
+/// Handles callbacks scheduled with `call_in`, which are called once and then freed.
+extern "C" fn scheduled_handler(server_handle: LavHandle, time: f64, userdata: *mut c_void) {
+    let server = borrowed_server(server_handle);
+    let mut scheduled_on = None;
+    unsafe {
+        callbacks::invoke::<Scheduled, _, _>(userdata, |scheduled| {
+            scheduled_on = Some((scheduled.id, scheduled.in_audio_thread));
+            if let Some(closure) = scheduled.closure.take() {
+                closure(&server, time);
+            }
+        });
+    }
+    match scheduled_on {
+        // the audio thread should do as little as possible, so the record is freed later
+        Some((id, true)) => registry::retire(server_handle, Slot::Scheduled(id)),
+        Some((id, false)) => drop(registry::detach(server_handle, Slot::Scheduled(id))),
+        None => {}
+    }
+}
+
+/// Iterator over the blocks of audio mixed by a server, returned by `Server::blocks`.
+pub struct Blocks<'server, 'lib: 'server, B: 'server = Unbranded> {
+    server: &'server Server<'lib, Offline, B>,
+    channels: u32,
+}
+
+impl<'server, 'lib, B> Iterator for Blocks<'server, 'lib, B> {
+    type Item = Result<Vec<f32>>;
+
+    fn next(&mut self) -> Option<Result<Vec<f32>>> {
+        let block_size = match self.server.get_block_size() {
+            Ok(block_size) => block_size as usize,
+            Err(e) => return Some(Err(e)),
+        };
+        let mut block = vec![0.0; block_size * self.channels as usize];
+        Some(
+            self.server
+                .get_block(self.channels, true, &mut block)
+                .map(|()| block),
+        )
     }
-  
-    pub fn create_buffer_node(&self) -> Result<buffer_node::BufferNode> {
-        let mut node_handle : libaudioverse_sys::LavHandle = 0;
-        check(unsafe { libaudioverse_sys::Lav_createBufferNode(self.handle, &mut node_handle) })?;
-        Ok(buffer_node::BufferNode { handle : node_handle })
+}
+
+/// A lock on a server, returned by `Server::lock`. The server is unlocked when this is dropped.
+/// The lock must be released by the thread which acquired it, so this guard cannot be sent to other threads.
+pub struct ServerLock<'server> {
+    handle: libaudioverse_sys::LavHandle,
+    server: PhantomData<*const &'server ()>, // keeps the guard from outliving the server, and makes it !Send
+}
+
+impl<'server> Drop for ServerLock<'server> {
+    fn drop(&mut self) {
+        unsafe {
+            Lav_serverUnlock(self.handle);
+        }
     }
-  */
 }
 
 #[test]
 fn can_create_server_with_default_audio_device() {
-    initialize().unwrap();
-    let server = Server::new().unwrap();
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
     server
         .set_output_device()
         .expect("Could not create default audio device");
-    shutdown().unwrap();
+}
+
+#[test]
+fn lock_is_released_after_batch() {
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    assert_eq!(server.batch(|| 42).unwrap(), 42);
+    drop(server.lock().unwrap());
+}
+
+#[test]
+fn renders_without_an_audio_device() {
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::construct_offline(&lib, 44100, 512).unwrap();
+    assert_eq!(server.get_block_size().unwrap(), 512);
+    assert_eq!(server.get_sampling_rate().unwrap(), 44100);
+    let rendered = server.render(2, Duration::from_millis(100)).unwrap();
+    assert_eq!(rendered.len(), 4410 * 2);
+    assert!(rendered.iter().all(|&sample| sample == 0.0));
+    let mut block = vec![0.0; 100];
+    assert!(server.get_block(2, false, &mut block).is_err());
+}
+
+#[test]
+#[ignore] // needs an audio output device
+fn switches_between_realtime_and_offline() {
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    server.set_output_device().unwrap();
+    let server = server.into_offline().unwrap();
+    let mut block = vec![0.0; 1024 * 2];
+    server.get_block(2, true, &mut block).unwrap();
+    server.into_realtime().set_output_device().unwrap();
+}
+
+#[test]
+fn keeps_the_graph_when_switching_modes() {
+    use buffer::Buffer;
+    use nodes::{BufferNode, Node};
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new(&lib).unwrap();
+    let buffer = Buffer::from_samples(&server, 44100, 1, &[0.5; 4410]).unwrap();
+    let node = BufferNode::new(&server).unwrap();
+    node.buffer().set(&buffer).unwrap();
+    node.connect_server(0).unwrap();
+    let server = server.into_offline().unwrap();
+    let rendered = server.render(2, Duration::from_millis(50)).unwrap();
+    assert!(rendered.iter().any(|&sample| sample != 0.0));
+    let _server = server.into_realtime();
+    node.looping().set(true).unwrap();
+}
+
+#[test]
+fn calls_scheduled_closures_while_rendering() {
+    use std::sync::mpsc;
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new_offline(&lib).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    let block_sender = sender.clone();
+    server
+        .set_block_callback(move |_, time| block_sender.send(("block", time)).unwrap())
+        .unwrap();
+    server
+        .call_in(Duration::from_millis(10), true, move |_, time| {
+            sender.send(("scheduled", time)).unwrap()
+        })
+        .unwrap();
+    server.render(2, Duration::from_millis(100)).unwrap();
+    server.clear_block_callback().unwrap();
+    let calls: Vec<_> = receiver.try_iter().collect();
+    assert!(calls.iter().any(|&(name, _)| name == "block"));
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|&&(name, _)| name == "scheduled")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn block_callback_can_replace_itself() {
+    use std::sync::mpsc;
+
+    let lib = Libaudioverse::new().unwrap();
+    let server = Server::new_offline(&lib).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    server
+        .set_block_callback(move |server, _| {
+            let second = sender.clone();
+            server
+                .set_block_callback(move |_, _| second.send("second").unwrap())
+                .unwrap();
+            // the closure keeps running after replacing itself, so what it owns must still be alive
+            sender.send("first").unwrap();
+        })
+        .unwrap();
+    server.render(2, Duration::from_millis(100)).unwrap();
+    server.clear_block_callback().unwrap();
+    let calls: Vec<_> = receiver.try_iter().collect();
+    assert_eq!(calls[0], "first");
+    assert!(calls.len() > 1);
+    assert!(calls[1..].iter().all(|&name| name == "second"));
+}
+
+#[test]
+fn builder_validates_and_applies_settings() {
+    let lib = Libaudioverse::new().unwrap();
+    let error = ServerBuilder::new()
+        .block_size(1000)
+        .build_offline(&lib)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind, ErrorKind::Range);
+    let server = ServerBuilder::new()
+        .sampling_rate(48000)
+        .block_size(256)
+        .threads(1)
+        .build_offline(&lib)
+        .unwrap();
+    assert_eq!(server.get_sampling_rate().unwrap(), 48000);
+    assert_eq!(server.get_block_size().unwrap(), 256);
+    assert_eq!(server.get_threads().unwrap(), 1);
 }